@@ -0,0 +1,338 @@
+//! A crate's public API: every `pub` item that can be named from outside the
+//! crate, under each path it can be named by.
+//!
+//! Items are reached from the crate root through `pub mod` declarations and
+//! `pub use` re-exports, including globs. A re-exported item keeps the
+//! signature of its declaration. Re-exports from other crates are listed as
+//! the `use` declaration itself.
+//!
+//! The variants of an enum, the `pub` fields of a struct or union and the
+//! items of a trait are listed under the item's path, as are a type's trait
+//! impls, derives and `pub` methods. An impl is matched to its type by name:
+//! it is either in the type's module, or no other type in the crate has
+//! that name.
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::{
+    Impl, Lib, MemberKind, Module, PublicIdentifier, PublicIdentifierType, Reference,
+    ReferenceKind, Visibility,
+};
+
+/// What an item of the API is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiKind {
+    /// A module, type, function, constant or other item, including the
+    /// items of inherent impls.
+    Item,
+    /// A trait impl or derive of a type.
+    Impl,
+    /// A variant of an enum.
+    Variant,
+    /// A `pub` field of a struct or union.
+    Field,
+    /// An item of a trait that implementations must provide.
+    Required,
+    /// An item of a trait with a default.
+    Provided,
+}
+
+/// An item of the API, and the path it can be named by.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ApiItem {
+    path: String,
+    kind: ApiKind,
+    signature: String,
+    /// Whether the enum or struct of a variant or field can be matched or
+    /// built exhaustively outside the crate.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    exhaustive: bool,
+    file: PathBuf,
+    line: usize,
+}
+
+impl ApiItem {
+    /// The path the item can be named by, e.g. `thigpen::render::Dot`. The
+    /// impls of a type have the type's path.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn kind(&self) -> ApiKind {
+        self.kind
+    }
+
+    /// The item's declaration on one line, which for a re-export is the
+    /// declaration of the re-exported item.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// For a variant or a field, whether code outside the crate can match
+    /// every variant of its enum or build its struct from its fields, so
+    /// that adding one breaks that code. `false` for other items.
+    pub fn is_exhaustive(&self) -> bool {
+        self.exhaustive
+    }
+
+    /// The file the item is declared in.
+    pub fn file(&self) -> &std::path::Path {
+        &self.file
+    }
+
+    /// The line the item is declared on, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    fn new(path: String, item: &PublicIdentifier) -> Self {
+        Self {
+            path,
+            kind: ApiKind::Item,
+            signature: item.signature().into(),
+            exhaustive: false,
+            file: item.file().into(),
+            line: item.line(),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.signature)
+    }
+}
+
+/// The public API of a crate, sorted by path. Its `Display` has one item per
+/// line, so two versions of it can be compared with `diff`. It serializes to
+/// a list of items, so it can be saved and compared later.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Api {
+    items: Vec<ApiItem>,
+}
+
+impl Api {
+    pub fn of(lib: &Lib) -> Self {
+        fn collect<'a>(module: &'a Module, modules: &mut Modules<'a>) {
+            modules.insert(
+                module.usepath(),
+                (module.interface(), module.references(), module.impls()),
+            );
+            module
+                .children()
+                .iter()
+                .for_each(|child| collect(child, modules));
+        }
+        let mut modules = HashMap::new();
+        modules.insert(lib.name(), (lib.interface(), lib.references(), lib.impls()));
+        lib.modules()
+            .iter()
+            .for_each(|module| collect(module, &mut modules));
+
+        // How many types have each name, to tell whether an impl outside a
+        // type's module is for that type.
+        let mut type_names: HashMap<&str, usize> = HashMap::new();
+        for (interface, _, _) in modules.values() {
+            for item in interface.iter().filter(|item| has_impls(item.kind())) {
+                *type_names.entry(item.name()).or_default() += 1;
+            }
+        }
+
+        let mut walk = Walk {
+            modules,
+            type_names,
+            walking: vec![],
+            items: vec![],
+        };
+        walk.module(lib.name(), lib.name());
+        let mut items = walk.items;
+        items.sort_by(|a, b| (&a.path, &a.signature).cmp(&(&b.path, &b.signature)));
+        items.dedup_by(|a, b| a.path == b.path && a.signature == b.signature);
+        Self { items }
+    }
+
+    pub fn items(&self) -> &[ApiItem] {
+        &self.items
+    }
+}
+
+impl std::fmt::Display for Api {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.items
+            .iter()
+            .try_for_each(|item| writeln!(f, "{}", item))
+    }
+}
+
+/// Whether items of `kind` are types that can have impls.
+fn has_impls(kind: PublicIdentifierType) -> bool {
+    matches!(
+        kind,
+        PublicIdentifierType::Enum | PublicIdentifierType::Struct | PublicIdentifierType::Union
+    )
+}
+
+/// The interface, references and impls of each module, by `usepath`.
+type Modules<'a> = HashMap<&'a str, (&'a [PublicIdentifier], &'a [Reference], &'a [Impl])>;
+
+struct Walk<'a> {
+    modules: Modules<'a>,
+    type_names: HashMap<&'a str, usize>,
+    /// The modules being walked, so that a module re-exporting its parent
+    /// doesn't lead to endless paths.
+    walking: Vec<&'a str>,
+    items: Vec<ApiItem>,
+}
+
+impl<'a> Walk<'a> {
+    /// Add the `pub` items of the module at `usepath`, which can be named
+    /// by `path`.
+    fn module(&mut self, usepath: &'a str, path: &str) {
+        if self.walking.contains(&usepath) {
+            return;
+        }
+        let Some((interface, _, _)) = self.modules.get(usepath).copied() else {
+            return;
+        };
+        self.walking.push(usepath);
+        for item in interface
+            .iter()
+            .filter(|item| *item.visibility() == Visibility::Public)
+        {
+            self.item(usepath, item, path, item.name());
+        }
+        self.walking.pop();
+    }
+
+    /// Add `item`, from the module at `usepath`, as `name` in the module
+    /// that can be named by `path`.
+    fn item(&mut self, usepath: &'a str, item: &'a PublicIdentifier, path: &str, name: &str) {
+        let item_path = format!("{}::{}", path, name);
+        match item.kind() {
+            PublicIdentifierType::Mod => {
+                self.items.push(ApiItem::new(item_path.clone(), item));
+                let child = format!("{}::{}", usepath, item.name());
+                if let Some((child, _)) = self.modules.get_key_value(child.as_str()) {
+                    self.module(child, &item_path);
+                }
+            }
+            PublicIdentifierType::Use => match self.reexported(usepath, item) {
+                Some(Reexport::Glob(module)) => self.module(module, path),
+                // A `use` re-exporting itself doesn't compile, but don't
+                // loop on one.
+                Some(Reexport::Item(module, reexported)) if !std::ptr::eq(reexported, item) => {
+                    self.item(module, reexported, path, name)
+                }
+                Some(Reexport::Item(..)) => {}
+                None => self.items.push(ApiItem::new(item_path, item)),
+            },
+            kind => {
+                if has_impls(kind) {
+                    self.impls(usepath, item.name(), &item_path);
+                }
+                self.members(item, &item_path);
+                self.items.push(ApiItem::new(item_path, item));
+            }
+        }
+    }
+
+    /// Add the impls of the type `name`, declared in the module at
+    /// `usepath`, which can be named by `path`.
+    fn impls(&mut self, usepath: &str, name: &str, path: &str) {
+        let is_unique = self.type_names.get(name) == Some(&1);
+        let impls = self
+            .modules
+            .iter()
+            .filter(|(module, _)| is_unique || **module == usepath)
+            .flat_map(|(_, (_, _, impls))| impls.iter())
+            .filter(|r#impl| r#impl.type_name() == name);
+        for r#impl in impls {
+            // An inherent impl only matters for its items.
+            if r#impl.r#trait().is_some() {
+                self.items.push(ApiItem {
+                    path: path.into(),
+                    kind: ApiKind::Impl,
+                    signature: r#impl.signature().into(),
+                    exhaustive: false,
+                    file: r#impl.file().into(),
+                    line: r#impl.line(),
+                });
+            }
+            self.items.extend(
+                r#impl
+                    .items()
+                    .iter()
+                    .filter(|item| *item.visibility() == Visibility::Public)
+                    .map(|item| ApiItem::new(format!("{}::{}", path, item.name()), item)),
+            );
+        }
+    }
+
+    /// Add the members of `item`, which can be named by `path`.
+    fn members(&mut self, item: &PublicIdentifier, path: &str) {
+        let members = item.members();
+        let exhaustive = !item.is_non_exhaustive()
+            && members
+                .iter()
+                .all(|member| member.visibility() == Some(&Visibility::Public));
+        self.items.extend(
+            members
+                .iter()
+                .filter(|member| member.visibility() == Some(&Visibility::Public))
+                .map(|member| {
+                    let kind = match member.kind() {
+                        MemberKind::Variant => ApiKind::Variant,
+                        MemberKind::Field => ApiKind::Field,
+                        MemberKind::Required => ApiKind::Required,
+                        MemberKind::Provided => ApiKind::Provided,
+                    };
+                    ApiItem {
+                        path: format!("{}::{}", path, member.name()),
+                        kind,
+                        signature: member.signature().into(),
+                        exhaustive: exhaustive && matches!(kind, ApiKind::Variant | ApiKind::Field),
+                        file: item.file().into(),
+                        line: member.line(),
+                    }
+                }),
+        );
+    }
+
+    /// What the `pub use` `item` in the module at `usepath` re-exports, or
+    /// `None` if it isn't in the crate.
+    fn reexported(&self, usepath: &str, item: &PublicIdentifier) -> Option<Reexport<'a>> {
+        let (_, references, _) = self.modules.get(usepath)?;
+        // The signature is `use` and the path as written, possibly renamed.
+        let written = item.signature().strip_prefix("use ")?;
+        let written = written.split(" as ").next()?;
+        let reference = references.iter().find(|reference| {
+            reference.kind() == ReferenceKind::Use
+                && reference.file() == item.file()
+                && reference.line() == item.line()
+                && reference.written() == written
+        })?;
+        let (name, module) = reference.path().split_last()?;
+        let (module, (interface, _, _)) = self
+            .modules
+            .get_key_value(module.join("::").as_str())
+            .map(|(module, entry)| (*module, *entry))?;
+        if name == "*" {
+            return Some(Reexport::Glob(module));
+        }
+        interface
+            .iter()
+            .find(|declared| {
+                declared.name() == name && *declared.visibility() == Visibility::Public
+            })
+            .map(|declared| Reexport::Item(module, declared))
+    }
+}
+
+enum Reexport<'a> {
+    /// Every `pub` item of the module at this `usepath`.
+    Glob(&'a str),
+    /// An item, and the `usepath` of the module declaring it.
+    Item(&'a str, &'a PublicIdentifier),
+}
@@ -0,0 +1,207 @@
+use std::path::Path;
+
+use thigpen::api::Api;
+use thigpen::Lib;
+
+use super::{display_path, written, Crate, Format};
+
+/// How many unchanged lines to show around each change.
+const CONTEXT: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Keep,
+    Remove,
+    Add,
+}
+
+/// The edits turning `old` into `new`, from their longest common subsequence.
+fn edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    // Most of a snapshot is unchanged, so only diff what lies between the
+    // common start and end.
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_middle, new_middle) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    // common[i][j] is the length of the longest common subsequence of
+    // old_middle[i..] and new_middle[j..].
+    let (n, m) = (old_middle.len(), new_middle.len());
+    let mut common = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if old_middle[i] == new_middle[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = vec![Edit::Keep; prefix];
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_middle[i] == new_middle[j] {
+            edits.push(Edit::Keep);
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && common[i + 1][j] >= common[i][j + 1]) {
+            edits.push(Edit::Remove);
+            i += 1;
+        } else {
+            edits.push(Edit::Add);
+            j += 1;
+        }
+    }
+    edits.extend(vec![Edit::Keep; suffix]);
+    edits
+}
+
+/// A unified diff from `old` to `new`, or `None` if they are the same.
+fn unified_diff(old_name: &str, old: &str, new_name: &str, new: &str) -> Option<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let edits = edits(&old, &new);
+    if edits.iter().all(|edit| *edit == Edit::Keep) {
+        return None;
+    }
+
+    // The position of each edit in `old` and `new`.
+    let mut positions = Vec::with_capacity(edits.len());
+    let (mut i, mut j) = (0, 0);
+    for edit in edits.iter() {
+        positions.push((i, j));
+        match edit {
+            Edit::Keep => {
+                i += 1;
+                j += 1;
+            }
+            Edit::Remove => i += 1,
+            Edit::Add => j += 1,
+        }
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", old_name, new_name);
+    let changes: Vec<usize> = (0..edits.len())
+        .filter(|index| edits[*index] != Edit::Keep)
+        .collect();
+    let mut next = 0;
+    while next < changes.len() {
+        // A hunk runs until there are more than two contexts' worth of
+        // unchanged lines between changes.
+        let start = changes[next].saturating_sub(CONTEXT);
+        let mut end = changes[next];
+        while next < changes.len() && changes[next] <= end + 2 * CONTEXT {
+            end = changes[next];
+            next += 1;
+        }
+        let end = (end + CONTEXT + 1).min(edits.len());
+
+        let count = |kind: Edit| {
+            edits[start..end]
+                .iter()
+                .filter(|edit| **edit == Edit::Keep || **edit == kind)
+                .count()
+        };
+        let (old_count, new_count) = (count(Edit::Remove), count(Edit::Add));
+        let (old_start, new_start) = positions[start];
+        // An empty range starts at the line before it.
+        let line = |start: usize, count: usize| if count == 0 { start } else { start + 1 };
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            line(old_start, old_count),
+            old_count,
+            line(new_start, new_count),
+            new_count
+        ));
+        for index in start..end {
+            let (i, j) = positions[index];
+            let (sign, text) = match edits[index] {
+                Edit::Keep => (' ', old[i]),
+                Edit::Remove => ('-', old[i]),
+                Edit::Add => ('+', new[j]),
+            };
+            diff.push(sign);
+            diff.push_str(text);
+            diff.push('\n');
+        }
+    }
+    Some(diff)
+}
+
+/// The snapshot at `path` as text, whether it was written as text or JSON,
+/// or is a model written by `--output-type json`.
+fn read_snapshot(path: &Path) -> Result<String, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| format!("Could not read {} - {err}", path.display()))?;
+    let parse_error =
+        |err: serde_json::Error| format!("Could not parse {} - {err}", path.display());
+    match contents.trim_start().chars().next() {
+        Some('[') => serde_json::from_str::<Api>(&contents)
+            .map(|api| api.to_string())
+            .map_err(parse_error),
+        Some('{') => Lib::from_json(&contents)
+            .map(|lib| Api::of(&lib).to_string())
+            .map_err(parse_error),
+        _ => Ok(contents),
+    }
+}
+
+pub fn run(crate_: &Crate, snapshot: Option<&Path>, check: bool, format: Format) -> i32 {
+    let api = Api::of(&crate_.lib);
+    let Some(snapshot) = snapshot else {
+        match format {
+            Format::Table => out!("{}", api),
+            Format::Json => {
+                written(serde_json::to_writer_pretty(std::io::stdout().lock(), &api));
+                outln!();
+            }
+        }
+        return 0;
+    };
+
+    if !check {
+        let contents = match format {
+            Format::Table => api.to_string(),
+            Format::Json => serde_json::to_string_pretty(&api).unwrap() + "\n",
+        };
+        if let Err(err) = std::fs::write(snapshot, contents) {
+            eprintln!("Could not write {} - {err}", snapshot.display());
+            return 2;
+        }
+        outln!(
+            "Wrote {} items of the API of {} to {}",
+            api.items().len(),
+            crate_.lib.name(),
+            display_path(crate_, snapshot)
+        );
+        return 0;
+    }
+
+    let committed = match read_snapshot(snapshot) {
+        Ok(committed) => committed,
+        Err(err) => {
+            eprintln!("{err}");
+            return 2;
+        }
+    };
+    let name = display_path(crate_, snapshot).to_string();
+    let api = api.to_string();
+    match unified_diff(&name, &committed, &format!("{} (current)", name), &api) {
+        None => {
+            outln!("The API of {} matches {}", crate_.lib.name(), name);
+            0
+        }
+        Some(diff) => {
+            out!("{}", diff);
+            1
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use thigpen::graph::ModuleGraph;
+use thigpen::rules::{Rules, RulesError};
+use thigpen::source::Source;
+
+use super::{display_path, Crate};
+
+pub fn run(crate_: &Crate, config: &Path, source: &dyn Source) -> i32 {
+    let rules = source
+        .read(config)
+        .map_err(RulesError::Io)
+        .and_then(|contents| Rules::from_toml(&contents));
+    let rules = match rules {
+        Ok(rules) => rules,
+        Err(err) => {
+            eprintln!("Could not read rules from {} - {err}", config.display());
+            return 2;
+        }
+    };
+    let lib = &crate_.lib;
+    let graph = ModuleGraph::new(lib);
+    let violations = rules.check(&graph);
+    if violations.is_empty() {
+        outln!(
+            "{} follows all {} rules in {}",
+            lib.name(),
+            rules.rules().len(),
+            display_path(crate_, config)
+        );
+        return 0;
+    }
+
+    for violation in violations.iter() {
+        let dependency = violation.dependency();
+        let reference = dependency.reference();
+        outln!(
+            "{}:{}: {} -> {}: {} {}",
+            display_path(crate_, reference.file()),
+            reference.line(),
+            dependency.from(),
+            dependency.to(),
+            reference.kind(),
+            reference.written()
+        );
+        outln!("  breaks: {}", violation.rule());
+    }
+    outln!("{} violations", violations.len());
+    1
+}
@@ -0,0 +1,33 @@
+use thigpen::graph::ModuleGraph;
+
+use super::{display_path, Crate};
+
+pub fn run(crate_: &Crate, ignore_parents: bool) -> i32 {
+    let lib = &crate_.lib;
+    let mut graph = ModuleGraph::new(lib);
+    if ignore_parents {
+        graph = graph.without_ancestors();
+    }
+    let cycles = graph.cycles();
+    if cycles.is_empty() {
+        outln!("No cycles between modules of {}", lib.name());
+        return 0;
+    }
+
+    for (index, cycle) in cycles.iter().enumerate() {
+        outln!("cycle {}: {}", index + 1, cycle.modules().join(", "));
+        for dependency in cycle.dependencies() {
+            let reference = dependency.reference();
+            outln!(
+                "  {} -> {}: {}:{}: {} {}",
+                dependency.from(),
+                dependency.to(),
+                display_path(crate_, reference.file()),
+                reference.line(),
+                reference.kind(),
+                reference.written()
+            );
+        }
+    }
+    1
+}
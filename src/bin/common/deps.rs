@@ -0,0 +1,98 @@
+use thigpen::{Module, Reference};
+
+use super::{dependency_names, Crate};
+
+/// Whether any of `references` is a path into the crate `name`.
+fn uses(references: &[&Reference], name: &str) -> bool {
+    references
+        .iter()
+        .any(|reference| reference.path().first().map(String::as_str) == Some(name))
+}
+
+pub fn run(crate_: &Crate) -> i32 {
+    fn collect<'a>(module: &'a Module, references: &mut Vec<&'a Reference>) {
+        references.extend(module.references());
+        module
+            .children()
+            .iter()
+            .for_each(|child| collect(child, references));
+    }
+    let lib = &crate_.lib;
+    let Some(manifest) = &crate_.manifest else {
+        eprintln!("A model has no Cargo.toml to check the dependencies of");
+        return 2;
+    };
+    let targets = || manifest.target.values();
+
+    let binaries: Vec<Reference> = ["src/main.rs", "src/bin"]
+        .iter()
+        .flat_map(|path| Reference::find_in_source(&crate_.root.join(path), &*crate_.source))
+        .collect();
+    let mut src: Vec<&Reference> = lib.references().iter().chain(binaries.iter()).collect();
+    lib.modules()
+        .iter()
+        .for_each(|module| collect(module, &mut src));
+    let tests: Vec<Reference> = ["tests", "benches", "examples"]
+        .iter()
+        .flat_map(|path| Reference::find_in_source(&crate_.root.join(path), &*crate_.source))
+        .collect();
+    let tests: Vec<&Reference> = tests.iter().collect();
+    let build: Vec<Reference> =
+        Reference::find_in_source(&crate_.root.join("build.rs"), &*crate_.source);
+    let build: Vec<&Reference> = build.iter().collect();
+
+    let mut problems = 0;
+    let mut report = |table: &str, name: &str, problem: &str| {
+        outln!("[{}] {}: {}", table, name, problem);
+        problems += 1;
+    };
+    for name in dependency_names(
+        &manifest.dependencies,
+        targets().map(|target| &target.dependencies),
+    ) {
+        if uses(&src, &name) {
+            continue;
+        }
+        if uses(&tests, &name) {
+            report(
+                "dependencies",
+                &name,
+                "only used by tests, benches or examples; move it to [dev-dependencies]",
+            );
+        } else if uses(&build, &name) {
+            report(
+                "dependencies",
+                &name,
+                "only used by build.rs; move it to [build-dependencies]",
+            );
+        } else {
+            report("dependencies", &name, "never used");
+        }
+    }
+    // Unit tests in src/ can use dev-dependencies too.
+    for name in dependency_names(
+        &manifest.dev_dependencies,
+        targets().map(|target| &target.dev_dependencies),
+    ) {
+        if !uses(&src, &name) && !uses(&tests, &name) {
+            report("dev-dependencies", &name, "never used");
+        }
+    }
+    for name in dependency_names(
+        &manifest.build_dependencies,
+        targets().map(|target| &target.build_dependencies),
+    ) {
+        if !uses(&build, &name) {
+            report("build-dependencies", &name, "never used");
+        }
+    }
+
+    if problems == 0 {
+        outln!(
+            "Every dependency of {} is used where it is declared",
+            lib.name()
+        );
+        return 0;
+    }
+    1
+}
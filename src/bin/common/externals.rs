@@ -0,0 +1,43 @@
+use thigpen::graph::ModuleGraph;
+
+use super::{display_path, Crate};
+
+pub fn run(crate_: &Crate) -> i32 {
+    let lib = &crate_.lib;
+    let graph = ModuleGraph::new(lib);
+    let mut dependencies = graph.external_dependencies().to_vec();
+    if dependencies.is_empty() {
+        outln!(
+            "No module of {} uses a crate from [dependencies]",
+            lib.name()
+        );
+        return 0;
+    }
+    dependencies.sort_by_key(|dependency| dependency.to());
+
+    for name in lib.external_crates() {
+        let uses: Vec<_> = dependencies
+            .iter()
+            .filter(|dependency| dependency.to() == name)
+            .collect();
+        let mut modules: Vec<&str> = uses.iter().map(|dependency| dependency.from()).collect();
+        modules.dedup();
+        if modules.is_empty() {
+            outln!("{}: not used", name);
+            continue;
+        }
+        outln!("{}: {}", name, modules.join(", "));
+        for dependency in uses {
+            let reference = dependency.reference();
+            outln!(
+                "  {}: {}:{}: {} {}",
+                dependency.from(),
+                display_path(crate_, reference.file()),
+                reference.line(),
+                reference.kind(),
+                reference.written()
+            );
+        }
+    }
+    0
+}
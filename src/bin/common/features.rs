@@ -0,0 +1,85 @@
+use thigpen::features::{FeatureMap, Gated};
+
+use super::{display_path, Crate};
+
+/// The features the manifest declares: those in [features], and the
+/// implicit feature of each optional dependency that no feature refers to
+/// with `dep:`.
+fn declared(manifest: &cargo_toml::Manifest) -> Vec<String> {
+    let explicit = manifest
+        .features
+        .values()
+        .flatten()
+        .filter_map(|enables| enables.strip_prefix("dep:"))
+        .collect::<Vec<_>>();
+    let optional = manifest
+        .dependencies
+        .iter()
+        .chain(
+            manifest
+                .target
+                .values()
+                .flat_map(|target| target.dependencies.iter()),
+        )
+        .filter(|(name, dependency)| dependency.optional() && !explicit.contains(&name.as_str()))
+        .map(|(name, _)| name.clone());
+    let mut names: Vec<String> = manifest.features.keys().cloned().chain(optional).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn describe(crate_: &Crate, gated: &Gated) -> String {
+    match gated {
+        Gated::Module(module) => match module.file() {
+            Some(file) => format!("mod {} ({})", module.usepath(), display_path(crate_, file)),
+            None => format!("mod {}", module.usepath()),
+        },
+        Gated::Item(module, item) => format!(
+            "{} {}::{} ({}:{})",
+            item.kind(),
+            module,
+            item.name(),
+            display_path(crate_, item.file()),
+            item.line()
+        ),
+    }
+}
+
+pub fn run(crate_: &Crate) -> i32 {
+    let map = FeatureMap::of(&crate_.lib);
+    let Some(manifest) = &crate_.manifest else {
+        eprintln!("A model has no Cargo.toml to find the declared features in");
+        return 2;
+    };
+    let declared = declared(manifest);
+
+    let mut undeclared = 0;
+    for name in declared.iter() {
+        if map.get(name).is_none() {
+            outln!("{}: gates nothing", name);
+        }
+    }
+    for (name, feature) in map.features() {
+        if declared.iter().any(|declared| declared == name) {
+            outln!("{}:", name);
+        } else {
+            outln!("{}: not declared in Cargo.toml, so never enabled", name);
+            undeclared += 1;
+        }
+        for (verb, gated) in [
+            ("adds", feature.adds()),
+            ("removes", feature.removes()),
+            ("alters", feature.alters()),
+        ] {
+            for gated in gated {
+                outln!("  {} {}", verb, describe(crate_, gated));
+            }
+        }
+    }
+    if undeclared > 0 {
+        1
+    } else {
+        0
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io;
+use std::path::Path;
+
+use thigpen::api::Api;
+use thigpen::graph::ModuleGraph;
+use thigpen::metrics::ModuleMetrics;
+use thigpen::render::escape::csv_field;
+use thigpen::source::git;
+use thigpen::{Lib, Module, Visibility};
+
+use super::{open_at, written, Crate, SeriesFormat};
+
+/// The size and coupling of the crate as of one revision.
+#[derive(serde::Serialize)]
+struct Sample {
+    /// The tag, or the abbreviated commit hash.
+    revision: String,
+    commit: String,
+    /// The commit date, as YYYY-MM-DD.
+    date: String,
+    modules: usize,
+    /// The number of lines `thigpen api` would list.
+    api_items: usize,
+    /// The number of `pub` items of each kind, in every module.
+    public_items: BTreeMap<String, usize>,
+    /// The number of modules depending on another, counting each pair once.
+    dependencies: usize,
+    average_instability: f64,
+    average_distance: f64,
+}
+
+impl Sample {
+    fn of(revision: &str, commit: &str, date: &str, lib: &Lib) -> Self {
+        fn collect<'a>(module: &'a Module, modules: &mut Vec<&'a Module>) {
+            modules.push(module);
+            module
+                .children()
+                .iter()
+                .for_each(|child| collect(child, modules));
+        }
+        let mut modules = vec![];
+        lib.modules()
+            .iter()
+            .for_each(|module| collect(module, &mut modules));
+
+        let mut public_items = BTreeMap::new();
+        for item in lib
+            .interface()
+            .iter()
+            .chain(modules.iter().flat_map(|module| module.interface()))
+            .filter(|item| *item.visibility() == Visibility::Public)
+        {
+            *public_items.entry(item.kind().to_string()).or_default() += 1;
+        }
+
+        let graph = ModuleGraph::new(lib);
+        let dependencies: HashSet<(&str, &str)> = graph
+            .dependencies()
+            .iter()
+            .map(|dependency| (dependency.from(), dependency.to()))
+            .collect();
+        let metrics = ModuleMetrics::of(lib);
+        let count = metrics.len() as f64;
+
+        Self {
+            revision: revision.into(),
+            commit: commit.into(),
+            date: date.into(),
+            // The crate root is a module too.
+            modules: modules.len() + 1,
+            api_items: Api::of(lib).items().len(),
+            public_items,
+            dependencies: dependencies.len(),
+            average_instability: metrics.iter().map(ModuleMetrics::instability).sum::<f64>()
+                / count,
+            average_distance: metrics.iter().map(ModuleMetrics::distance).sum::<f64>() / count,
+        }
+    }
+}
+
+/// The revisions to sample, oldest first: every `every`th commit along the
+/// first parents of `HEAD`, ending with `HEAD`, or every tag if there is no
+/// `every`.
+fn revisions(root: &Path, every: Option<usize>) -> io::Result<Vec<String>> {
+    let revisions = match every {
+        None => git(
+            root,
+            &[
+                "for-each-ref",
+                "--sort=creatordate",
+                "--format=%(refname:short)",
+                "refs/tags",
+            ],
+        )?
+        .lines()
+        .map(String::from)
+        .collect(),
+        Some(every) => {
+            let commits = git(
+                root,
+                &["rev-list", "--first-parent", "--abbrev-commit", "HEAD"],
+            )?;
+            let mut commits: Vec<String> =
+                commits.lines().step_by(every).map(String::from).collect();
+            commits.reverse();
+            commits
+        }
+    };
+    Ok(revisions)
+}
+
+fn write_csv(samples: &[Sample]) {
+    let kinds: BTreeSet<&str> = samples
+        .iter()
+        .flat_map(|sample| sample.public_items.keys())
+        .map(String::as_str)
+        .collect();
+    let row = |fields: Vec<String>| {
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|field| csv_field(field, ',').into_owned())
+            .collect();
+        outln!("{}", fields.join(","));
+    };
+
+    let mut header: Vec<String> = ["revision", "commit", "date", "modules", "api_items"]
+        .map(String::from)
+        .into();
+    header.extend(
+        kinds
+            .iter()
+            .map(|kind| format!("pub_{}", kind.replace(' ', "_"))),
+    );
+    header.extend(["dependencies", "average_instability", "average_distance"].map(String::from));
+    row(header);
+    for sample in samples {
+        let mut fields = vec![
+            sample.revision.clone(),
+            sample.commit.clone(),
+            sample.date.clone(),
+            sample.modules.to_string(),
+            sample.api_items.to_string(),
+        ];
+        fields.extend(kinds.iter().map(|kind| {
+            sample
+                .public_items
+                .get(*kind)
+                .copied()
+                .unwrap_or_default()
+                .to_string()
+        }));
+        fields.extend([
+            sample.dependencies.to_string(),
+            format!("{:.3}", sample.average_instability),
+            format!("{:.3}", sample.average_distance),
+        ]);
+        row(fields);
+    }
+}
+
+pub fn run(crate_: &Crate, every: Option<usize>, format: SeriesFormat) -> i32 {
+    let revisions = match revisions(&crate_.root, every) {
+        Ok(revisions) => revisions,
+        Err(err) => {
+            eprintln!("Could not list revisions - {err}");
+            return 2;
+        }
+    };
+    if revisions.is_empty() {
+        eprintln!("There are no revisions to sample; try --every");
+        return 2;
+    }
+
+    let mut samples = vec![];
+    for revision in revisions {
+        log::debug!("Sampling {revision}");
+        let commit = git(&crate_.root, &["log", "-1", "--format=%H %cs", &revision]);
+        let Some((commit, date)) = commit
+            .as_deref()
+            .ok()
+            .and_then(|line| line.trim().split_once(' '))
+        else {
+            eprintln!("warning: skipping {revision}: it is not a commit");
+            continue;
+        };
+        // The crate may not have existed yet, or not been a library.
+        match open_at(&crate_.root, Some(commit)) {
+            Ok(sampled) => samples.push(Sample::of(&revision, commit, date, &sampled.lib)),
+            Err(err) => eprintln!("warning: skipping {revision}: {err}"),
+        }
+    }
+
+    match format {
+        SeriesFormat::Csv => write_csv(&samples),
+        SeriesFormat::Json => {
+            written(serde_json::to_writer_pretty(
+                std::io::stdout().lock(),
+                &samples,
+            ));
+            outln!();
+        }
+    }
+    0
+}
@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+
+use thigpen::impact::Impact;
+use thigpen::source::git;
+use thigpen::Visibility;
+
+use super::{display_path, Crate};
+
+/// The files under `root` that differ between `revision` and the working
+/// tree, including untracked files, according to the local git repository.
+fn changed_since(root: &Path, revision: &str) -> std::io::Result<Vec<PathBuf>> {
+    let files = |args: &[&str]| -> std::io::Result<Vec<PathBuf>> {
+        Ok(git(root, args)?
+            .lines()
+            .map(|line| root.join(line))
+            .collect())
+    };
+    // `--` keeps a revision that looks like a file from being taken as one.
+    let mut changed = files(&["diff", "--name-only", "--relative", revision, "--"])?;
+    changed.extend(files(&["ls-files", "--others", "--exclude-standard"])?);
+    Ok(changed)
+}
+
+pub fn run(crate_: &Crate, mut files: Vec<PathBuf>, since: Option<&str>) -> i32 {
+    // Files from git that aren't part of the crate (README.md, tests/ and
+    // such) are expected; those given explicitly are worth a warning.
+    let given: Vec<PathBuf> = files
+        .iter()
+        .map(|file| std::fs::canonicalize(file).unwrap_or_else(|_| file.clone()))
+        .collect();
+    if let Some(revision) = since {
+        match changed_since(&crate_.root, revision) {
+            Ok(changed) => files.extend(changed),
+            Err(err) => {
+                eprintln!("Could not list the files changed since {revision} - {err}");
+                return 2;
+            }
+        }
+    }
+    let lib = &crate_.lib;
+    let impact = Impact::of(lib, &files);
+    for file in impact.unknown().iter().filter(|file| given.contains(file)) {
+        eprintln!(
+            "warning: {} is not part of {}",
+            display_path(crate_, file),
+            lib.name()
+        );
+    }
+    if impact.changed().is_empty() {
+        outln!("No module of {} changed", lib.name());
+        return 0;
+    }
+
+    outln!("Changed modules:");
+    for module in impact.changed() {
+        outln!("  {}", module);
+    }
+    outln!("Affected modules:");
+    for module in impact.affected() {
+        outln!("  {}", module);
+    }
+    outln!("Affected pub items:");
+    for (module, item) in impact
+        .items()
+        .iter()
+        .filter(|(_, item)| *item.visibility() == Visibility::Public)
+    {
+        outln!(
+            "  {}:{}: {} {}::{}",
+            display_path(crate_, item.file()),
+            item.line(),
+            item.kind(),
+            module,
+            item.name()
+        );
+    }
+    0
+}
@@ -0,0 +1,51 @@
+use thigpen::metrics::ModuleMetrics;
+
+use super::{written, Crate, Format};
+
+pub fn run(crate_: &Crate, format: Format) -> i32 {
+    let mut metrics = ModuleMetrics::of(&crate_.lib);
+    metrics.sort_by(|a, b| {
+        b.distance()
+            .total_cmp(&a.distance())
+            .then_with(|| a.module().cmp(b.module()))
+    });
+
+    match format {
+        Format::Json => {
+            written(serde_json::to_writer_pretty(
+                std::io::stdout().lock(),
+                &metrics,
+            ));
+            outln!();
+        }
+        Format::Table => {
+            let width = metrics
+                .iter()
+                .map(|metrics| metrics.module().len())
+                .chain(["module".len()])
+                .max()
+                .unwrap_or_default();
+            outln!(
+                "{:width$}  {:>4}  {:>4}  {:>4}  {:>4}  {:>4}",
+                "module",
+                "Ca",
+                "Ce",
+                "I",
+                "A",
+                "D"
+            );
+            for metrics in metrics.iter() {
+                outln!(
+                    "{:width$}  {:>4}  {:>4}  {:>4.2}  {:>4.2}  {:>4.2}",
+                    metrics.module(),
+                    metrics.afferent(),
+                    metrics.efferent(),
+                    metrics.instability(),
+                    metrics.abstractness(),
+                    metrics.distance()
+                );
+            }
+        }
+    }
+    0
+}
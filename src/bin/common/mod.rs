@@ -0,0 +1,603 @@
+//! What the `thigpen` and `cargo thigpen` commands share, once they know
+//! which directory to look at.
+use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use clap::builder::{PossibleValuesParser, RangedU64ValueParser};
+use clap::{Subcommand, ValueEnum};
+use simplelog::{ConfigBuilder, WriteLogger};
+
+use thigpen::filter::Filter;
+use thigpen::pattern::ModulePattern;
+use thigpen::render::{Dot, Mermaid, Renderers, Tree};
+use thigpen::source::{FileSystem, Git, Source};
+use thigpen::{DiagnosticKind, Lib, PublicIdentifierType, Reference};
+
+/// `print!`, stopping quietly if stdout is closed, as when the output is
+/// piped into `head`.
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::common::written({
+            use std::io::Write as _;
+            write!(std::io::stdout().lock(), $($arg)*)
+        })
+    };
+}
+
+/// `println!`, stopping quietly if stdout is closed.
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        $crate::common::written({
+            use std::io::Write as _;
+            writeln!(std::io::stdout().lock(), $($arg)*)
+        })
+    };
+}
+
+mod api;
+mod check;
+mod cycles;
+mod deps;
+mod externals;
+mod features;
+mod history;
+mod impact;
+mod metrics;
+mod orphans;
+mod semver;
+mod stats;
+mod tighten;
+mod unused;
+mod why;
+
+#[derive(clap::Args, Debug)]
+pub struct RenderArgs {
+    #[arg(long, default_value = "mermaid", value_parser = PossibleValuesParser::new(Renderers::default().names()))]
+    output_type: String,
+    #[arg(short)]
+    output: Option<String>,
+    /// Show the module at this path, e.g. crate::net, and the modules
+    /// beneath it, in place of the whole crate
+    #[arg(long, value_name = "MODULE")]
+    root: Option<String>,
+    /// Only descend this many levels into the module tree
+    #[arg(long)]
+    depth: Option<usize>,
+    /// Only show modules matching one of these patterns, e.g. 'crate::api::**',
+    /// along with the modules above them
+    #[arg(long, value_name = "PATTERN", value_parser = ModulePattern::new)]
+    include: Vec<ModulePattern>,
+    /// Hide modules matching one of these patterns, e.g. '**::tests', and the
+    /// modules beneath them
+    #[arg(long, value_name = "PATTERN", value_parser = ModulePattern::new)]
+    exclude: Vec<ModulePattern>,
+    /// Only show public items of these kinds, e.g. trait,struct
+    #[arg(long, value_delimiter = ',')]
+    kinds: Option<Vec<PublicIdentifierType>>,
+    /// Write one mermaid diagram per top-level module, plus an index, into the
+    /// directory given with -o. With a threshold, any module with more than
+    /// that many modules beneath it also gets a diagram of its own
+    #[arg(long, value_name = "THRESHOLD", num_args = 0..=1)]
+    split: Option<Option<usize>>,
+    /// Show the crates from [dependencies] that each module uses
+    #[arg(long)]
+    externals: bool,
+    /// Size each module by its lines of code (dot output only)
+    #[arg(long)]
+    scale: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List the crate's public API, one item per line, sorted: every `pub`
+    /// item that can be named from outside the crate, by its full path,
+    /// with its signature
+    Api {
+        /// Write the listing to this file instead
+        #[arg(long, value_name = "FILE")]
+        snapshot: Option<PathBuf>,
+        /// Compare the API with the snapshot instead of writing it, printing
+        /// a unified diff. Exits with 1 if they differ, and 2 if the
+        /// snapshot can't be read
+        #[arg(long, requires = "snapshot")]
+        check: bool,
+        /// With json, also list whether each item is a variant, a field, a
+        /// trait item with or without a default, or an impl, and where it is
+        /// declared. `semver` can compare a crate with a snapshot in JSON
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Report cycles in the dependencies between modules, along with the
+    /// `use` declarations and paths that create them. Exits with 1 if
+    /// there are any
+    Cycles {
+        /// Leave out references from a module to its parent or any module
+        /// above it, e.g. `super::Error`, so that a parent and a child
+        /// referring to each other are not a cycle
+        #[arg(long)]
+        ignore_parents: bool,
+    },
+    /// Check the dependencies between modules against the rules in a TOML
+    /// file. Exits with 1 if any rule is broken, and 2 if the rules could
+    /// not be read
+    Check {
+        /// The rules to check [default: thigpen.toml next to Cargo.toml]
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+    /// List the dependencies in the manifest that no code uses, and those
+    /// that belong in [dev-dependencies] or [build-dependencies] instead.
+    /// Exits with 1 if there are any
+    Deps,
+    /// List the crates from [dependencies] that each module uses, along
+    /// with the `use` declarations and paths that use them
+    Externals,
+    /// List what each cargo feature does to the crate: the modules and items
+    /// that only exist when it is enabled, those that disappear, and those
+    /// that get extra attributes, from `#[cfg]` and `#[cfg_attr]`. Exits
+    /// with 1 if an attribute mentions a feature the manifest doesn't declare
+    Features,
+    /// Analyze the crate as of every tag, or every few commits, and print
+    /// how its modules, public items and coupling changed over time, oldest
+    /// first, straight from the git repository
+    History {
+        /// Sample every Nth commit along the first parents of HEAD, ending
+        /// with HEAD, instead of every tag
+        #[arg(long, value_name = "N", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        every: Option<usize>,
+        #[arg(long, value_enum, default_value_t = SeriesFormat::Csv)]
+        format: SeriesFormat,
+    },
+    /// List the modules, and the pub items, that a change to some files can
+    /// affect: the modules in those files, and every module depending on
+    /// them, directly or indirectly
+    Impact {
+        /// The changed files
+        #[arg(required_unless_present = "since")]
+        files: Vec<PathBuf>,
+        /// Also count the files changed since this git revision, e.g. main,
+        /// including uncommitted and untracked changes
+        #[arg(long, value_name = "REF")]
+        since: Option<String>,
+    },
+    /// Report coupling metrics for every module: afferent and efferent
+    /// coupling, instability, abstractness and distance from the main
+    /// sequence, furthest from it first
+    Metrics {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// List the .rs files in the crate's source directory that no `mod`
+    /// declaration reaches, and so are never compiled. Exits with 1 if there
+    /// are any
+    Orphans,
+    /// Compare the public API of two versions of the crate, classifying
+    /// each change as major (a removed or changed item, a new trait item
+    /// without a default, a new variant of an enum that isn't
+    /// `#[non_exhaustive]`), minor (an addition) or a patch, and suggest the
+    /// next version from the one in Cargo.toml. Exits with 1 if the new
+    /// version's Cargo.toml moves on from the old one by less than its
+    /// changes need, and 2 if a version can't be read
+    Semver {
+        /// The old version: a directory holding the crate, a git revision,
+        /// e.g. v1.2.0, a file written by `api --format json --snapshot`, or
+        /// a model written by --output-type json
+        old: String,
+        /// The new version, in the same forms [default: the crate as it is]
+        new: Option<String>,
+    },
+    /// Report the size of every module: its lines of code, comments and
+    /// blank lines, its items by kind, and the number and cyclomatic
+    /// complexity of its functions
+    Stats {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// List `pub` items that nothing outside their own module refers to.
+    /// Exits with 1 if there are any
+    Unused {
+        /// Also count references from src/bin/, tests/, examples/ and benches/
+        #[arg(long)]
+        tests: bool,
+    },
+    /// Suggest a narrower visibility for items that are more visible than
+    /// the references to them need, leaving the crate's API alone. Exits
+    /// with 1 if there are any suggestions
+    Tighten {
+        /// Also count references from src/bin/, tests/, examples/ and benches/
+        #[arg(long)]
+        tests: bool,
+    },
+    /// Explain why one module depends on another, with the `use` declaration
+    /// or path behind each step of the shortest chain of dependencies
+    /// between them. Exits with 1 if there is no such chain
+    Why {
+        /// The depending module, e.g. crate::api, or a pattern
+        #[arg(value_parser = ModulePattern::new)]
+        from: ModulePattern,
+        /// The module depended upon, e.g. crate::infra::db, or a pattern
+        #[arg(value_parser = ModulePattern::new)]
+        to: ModulePattern,
+    },
+}
+
+/// How a command writes its report.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+/// How `history` writes its time series.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SeriesFormat {
+    Csv,
+    Json,
+}
+
+/// A crate being analyzed.
+pub struct Crate {
+    /// The directory holding the crate's manifest.
+    pub root: PathBuf,
+    /// The crate's manifest, or `None` if it was read from a model.
+    pub manifest: Option<cargo_toml::Manifest>,
+    pub lib: Lib,
+    /// Where the crate's files were read from.
+    pub source: Box<dyn Source>,
+}
+
+impl Crate {
+    /// The crate, with its library told about its [dependencies]. A model
+    /// keeps the external crates it was written with.
+    fn with_external_crates(self) -> Self {
+        if self.manifest.is_none() {
+            return self;
+        }
+        let dependencies = self.dependencies();
+        Self {
+            lib: self.lib.with_external_crates(dependencies),
+            ..self
+        }
+    }
+
+    /// The names of the crates in `[dependencies]`, including target
+    /// specific ones, as they are written in paths.
+    fn dependencies(&self) -> Vec<String> {
+        let Some(manifest) = &self.manifest else {
+            return vec![];
+        };
+        dependency_names(
+            &manifest.dependencies,
+            manifest.target.values().map(|target| &target.dependencies),
+        )
+    }
+
+    /// The references to the crate from its binaries, tests, examples and
+    /// benchmarks.
+    fn references_from_targets(&self) -> Vec<Reference> {
+        ["src/bin", "tests", "examples", "benches"]
+            .iter()
+            .flat_map(|dir| Reference::find_in_source(&self.root.join(dir), &*self.source))
+            .collect()
+    }
+}
+
+/// The names of the crates in a dependency table and its target specific
+/// counterparts, as they are written in paths.
+fn dependency_names<'a>(
+    table: &'a cargo_toml::DepsSet,
+    targets: impl Iterator<Item = &'a cargo_toml::DepsSet>,
+) -> Vec<String> {
+    let mut names: Vec<String> = table
+        .keys()
+        .chain(targets.flat_map(|table| table.keys()))
+        .map(|name| name.replace('-', "_"))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+pub fn init_logging(debug: bool) {
+    if debug {
+        let config = ConfigBuilder::new()
+            .set_max_level(log::LevelFilter::Debug)
+            .build();
+        WriteLogger::init(
+            log::LevelFilter::Debug,
+            config,
+            std::fs::File::create("thigpen-debug.log").expect("could not create debug log"),
+        )
+        .expect("Could not initialize logging");
+    }
+}
+
+fn find_cargo_toml(path: &Path, source: &dyn Source) -> Option<PathBuf> {
+    assert!(path.is_dir(), "Path is {}", path.to_str().unwrap());
+
+    let mut path = path.to_path_buf();
+    loop {
+        path.push("Cargo.toml");
+        if source.is_file(&path) {
+            return Some(path);
+        }
+        if !(path.pop() && path.pop()) {
+            return None;
+        }
+    }
+}
+
+/// The files next to a manifest, in the form cargo_toml looks for the
+/// implicit targets and the workspace of a package in.
+struct ManifestFiles<'a> {
+    source: &'a dyn Source,
+    /// The directory holding the manifest.
+    dir: &'a Path,
+}
+
+impl cargo_toml::AbstractFilesystem for ManifestFiles<'_> {
+    fn file_names_in(&self, rel_path: &str) -> std::io::Result<HashSet<Box<str>>> {
+        Ok(self
+            .source
+            .read_dir(&self.dir.join(rel_path))?
+            .iter()
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into())
+            .collect())
+    }
+
+    fn read_root_workspace(
+        &self,
+        rel_path_hint: Option<&str>,
+    ) -> std::io::Result<(Vec<u8>, PathBuf)> {
+        if let Some(dir) = rel_path_hint {
+            let dir = self.dir.join(dir);
+            return Ok((self.source.read(&dir.join("Cargo.toml"))?.into_bytes(), dir));
+        }
+        self.dir
+            .ancestors()
+            .skip(1)
+            .find_map(|dir| {
+                let contents = self.source.read(&dir.join("Cargo.toml")).ok()?;
+                contents
+                    .contains("[workspace]")
+                    .then(|| (contents.into_bytes(), dir.to_path_buf()))
+            })
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("no workspace above {}", self.dir.display()),
+                )
+            })
+    }
+}
+
+/// Find the manifest in `path` or any of its parents in `source`, and
+/// analyze the library it describes.
+fn open(path: &Path, source: Box<dyn Source>) -> Result<Crate, String> {
+    let cargo_toml_path = find_cargo_toml(path, &*source).ok_or_else(|| {
+        format!(
+            "Could not find Cargo.toml from any parent of {}",
+            path.to_str().unwrap()
+        )
+    })?;
+    log::debug!("Found manifest at {:?}", cargo_toml_path);
+    let root = cargo_toml_path.parent().unwrap().to_path_buf();
+
+    let manifest = source
+        .read(&cargo_toml_path)
+        .map_err(cargo_toml::Error::Io)
+        .and_then(|contents| cargo_toml::Manifest::from_str(&contents))
+        .and_then(|mut manifest| {
+            let files = ManifestFiles {
+                source: &*source,
+                dir: &root,
+            };
+            manifest.complete_from_abstract_filesystem::<cargo_toml::Value, _>(files, None)?;
+            Ok(manifest)
+        })
+        .map_err(|err| format!("Could not parse Cargo.toml - {err}"))?;
+    log::debug!("Manifest: {:?}", manifest);
+
+    let Some(product) = manifest.lib.clone() else {
+        return Err("thigpen does not (yet) support non-lib crates".into());
+    };
+    log::debug!("Analyzing library as {}", product.name.as_ref().unwrap());
+    let lib = Lib::from_source(
+        product.name.as_ref().unwrap(),
+        root.join(product.path.as_ref().unwrap()).as_path(),
+        &*source,
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(Crate {
+        root,
+        manifest: Some(manifest),
+        lib,
+        source,
+    })
+}
+
+/// The crate analyzed earlier and written to `model` by `--output-type json`.
+/// A model has no manifest, so `path` stands in for the directory holding it.
+fn open_model(path: &Path, model: &Path) -> Result<Crate, String> {
+    let lib = std::fs::read_to_string(model)
+        .map_err(|err| format!("Could not read {} - {err}", model.display()))
+        .and_then(|json| {
+            Lib::from_json(&json)
+                .map_err(|err| format!("Could not parse {} - {err}", model.display()))
+        })?;
+    Ok(Crate {
+        root: path.to_path_buf(),
+        manifest: None,
+        lib,
+        source: Box::new(FileSystem),
+    })
+}
+
+/// Analyze the crate at `path` as of the git revision `rev` of the
+/// repository holding it, or as it is on disk if there is no `rev`.
+fn open_at(path: &Path, rev: Option<&str>) -> Result<Crate, String> {
+    let Some(rev) = rev else {
+        return open(path, Box::new(FileSystem));
+    };
+    // Paths in the revision are under the top of the working tree, which git
+    // gives with symbolic links resolved.
+    let path = std::fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .map_err(|err| format!("Could not find {} - {err}", path.display()))?;
+    let git = Git::open(&path, rev).map_err(|err| format!("Could not read {rev} - {err}"))?;
+    open(&path, Box::new(git))
+}
+
+/// Find the manifest in `path` or any of its parents, and analyze the library
+/// it describes, as of the git revision `rev` if there is one, or read it from
+/// a `model` written earlier, warning about the modules whose files are amiss.
+/// Exits if there is no such library.
+pub fn load(path: &Path, rev: Option<&str>, model: Option<&Path>) -> Crate {
+    let crate_ = match model {
+        Some(model) => open_model(path, model),
+        None => open_at(path, rev),
+    };
+    let crate_ = match crate_ {
+        Ok(crate_) => crate_,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    };
+    for diagnostic in crate_.lib.diagnostics() {
+        let [mod_rs, rs] = diagnostic.candidates();
+        let (mod_rs, rs) = (display_path(&crate_, mod_rs), display_path(&crate_, rs));
+        eprint!(
+            "warning: {}:{}: module {} ",
+            display_path(&crate_, diagnostic.file()),
+            diagnostic.line(),
+            diagnostic.usepath(),
+        );
+        match diagnostic.kind() {
+            DiagnosticKind::MissingFile => {
+                eprintln!("has no file: neither {rs} nor {mod_rs} exists")
+            }
+            DiagnosticKind::AmbiguousFile => {
+                eprintln!("is in both {rs} and {mod_rs}, which rustc refuses; only reading {rs}")
+            }
+        }
+    }
+    crate_
+}
+
+pub fn render(crate_: Crate, args: RenderArgs) {
+    let mut filter = Filter::default()
+        .include(args.include)
+        .exclude(args.exclude);
+    if let Some(root) = &args.root {
+        filter = filter.root(root);
+    }
+    if let Some(depth) = args.depth {
+        filter = filter.depth(depth);
+    }
+    if let Some(kinds) = args.kinds {
+        filter = filter.kinds(kinds);
+    }
+    let crate_ = if args.externals {
+        crate_.with_external_crates()
+    } else {
+        crate_
+    };
+    let lib = match filter.apply(crate_.lib) {
+        Ok(lib) => lib,
+        Err(err) => {
+            eprintln!("Could not filter the crate - {err}");
+            std::process::exit(1);
+        }
+    };
+    let lib = &lib;
+    let mut renderers = Renderers::default();
+    renderers.register(
+        "tree",
+        Box::new(Tree::new(
+            None,
+            args.output.is_none() && std::io::stdout().is_terminal(),
+        )),
+    );
+    if args.scale {
+        if args.output_type != "dot" {
+            eprintln!("--scale only works with --output-type dot");
+            std::process::exit(1);
+        }
+        renderers.register("dot", Box::new(Dot::scaled()));
+    }
+    if let Some(threshold) = args.split {
+        if args.output_type != "mermaid" {
+            eprintln!("--split only works with --output-type mermaid");
+            std::process::exit(1);
+        }
+        if args.output.is_none() {
+            eprintln!("--split needs a directory to write to, given with -o");
+            std::process::exit(1);
+        }
+        renderers.register("mermaid", Box::new(Mermaid::split(threshold)));
+    }
+    // clap has already checked that the output type is registered.
+    let renderer = renderers.get(&args.output_type).unwrap();
+    match args.output {
+        Some(filename) => {
+            if let Err(err) = renderer.render_to_path(lib, Path::new(&filename)) {
+                eprintln!("Could not write output - {err}");
+                std::process::exit(1);
+            }
+        }
+        None => written(renderer.render(lib, &mut std::io::stdout().lock())),
+    }
+}
+
+/// Check the result of writing to stdout. If the reader has gone away, as
+/// `head` does once it has read enough, there is no one left to tell, so
+/// the program stops quietly; any other error ends it with 1.
+pub fn written<E: Into<std::io::Error>>(result: Result<(), E>) {
+    let Err(err) = result else {
+        return;
+    };
+    let err = err.into();
+    if err.kind() == std::io::ErrorKind::BrokenPipe {
+        std::process::exit(0);
+    }
+    eprintln!("Could not write output - {err}");
+    std::process::exit(1);
+}
+
+/// Run `command`, exiting with its status.
+pub fn run(crate_: Crate, command: Command) -> ! {
+    let status = match command {
+        Command::Api {
+            snapshot,
+            check,
+            format,
+        } => api::run(&crate_, snapshot.as_deref(), check, format),
+        Command::Cycles { ignore_parents } => cycles::run(&crate_, ignore_parents),
+        Command::Deps => deps::run(&crate_),
+        Command::Externals => externals::run(&crate_.with_external_crates()),
+        Command::Features => features::run(&crate_),
+        // The default rules are part of the crate; others are on disk.
+        Command::Check { config } => match config {
+            Some(config) => check::run(&crate_, &config, &FileSystem),
+            None => check::run(&crate_, &crate_.root.join("thigpen.toml"), &*crate_.source),
+        },
+        Command::History { every, format } => history::run(&crate_, every, format),
+        Command::Impact { files, since } => impact::run(&crate_, files, since.as_deref()),
+        Command::Metrics { format } => metrics::run(&crate_, format),
+        Command::Orphans => orphans::run(&crate_),
+        Command::Semver { old, new } => semver::run(&crate_, &old, new.as_deref()),
+        Command::Stats { format } => stats::run(&crate_, format),
+        Command::Unused { tests } => unused::run(&crate_, tests),
+        Command::Tighten { tests } => tighten::run(&crate_, tests),
+        Command::Why { from, to } => why::run(&crate_, &from, &to),
+    };
+    std::process::exit(status)
+}
+
+/// `path`, relative to the directory holding the crate's manifest if possible.
+fn display_path<'a>(crate_: &Crate, path: &'a Path) -> std::path::Display<'a> {
+    path.strip_prefix(&crate_.root).unwrap_or(path).display()
+}
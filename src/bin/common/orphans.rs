@@ -0,0 +1,23 @@
+use super::{display_path, Crate};
+
+pub fn run(crate_: &Crate) -> i32 {
+    let lib = &crate_.lib;
+    if crate_.manifest.is_none() {
+        eprintln!("A model has no files to find orphans among");
+        return 2;
+    }
+    let orphans = lib.orphans_in(&*crate_.source);
+    if orphans.is_empty() {
+        outln!(
+            "Every .rs file of {} is reached by a mod declaration",
+            lib.name()
+        );
+        return 0;
+    }
+
+    for orphan in orphans.iter() {
+        outln!("{}", display_path(crate_, orphan));
+    }
+    outln!("{} orphaned files", orphans.len());
+    1
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use thigpen::api::Api;
+use thigpen::semver::{self, Change, Level, Version};
+use thigpen::Lib;
+
+use super::{open_at, Crate};
+
+/// One side of the comparison.
+struct Side {
+    /// How the side was given, for messages.
+    name: String,
+    api: Api,
+    /// The `version` in the crate's Cargo.toml, if the side is a crate.
+    version: Option<String>,
+}
+
+impl Side {
+    fn of(name: &str, crate_: &Crate) -> Self {
+        Self {
+            name: name.into(),
+            api: Api::of(&crate_.lib),
+            version: crate_
+                .manifest
+                .as_ref()
+                .and_then(|manifest| manifest.package.as_ref())
+                .and_then(|package| package.version.get().ok())
+                .cloned(),
+        }
+    }
+
+    /// The side given by `spec`: a directory holding a crate, a snapshot
+    /// written by `thigpen api --format json`, a model written by
+    /// `--output-type json`, or a revision of the git
+    /// repository holding the crate at `root`, read without checking it out.
+    fn load(spec: &str, root: &Path) -> Result<Self, String> {
+        let path = Path::new(spec);
+        if path.is_dir() {
+            return open_at(path, None).map(|crate_| Self::of(spec, &crate_));
+        }
+        if path.is_file() {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|err| format!("Could not read {spec} - {err}"))?;
+            // An API is a list of items; a model is an object.
+            let api = if contents.trim_start().starts_with('[') {
+                serde_json::from_str(&contents)
+                    .map_err(|err| format!("Could not parse {spec} as a JSON API - {err}"))?
+            } else {
+                Lib::from_json(&contents)
+                    .map(|lib| Api::of(&lib))
+                    .map_err(|err| format!("Could not parse {spec} as a model - {err}"))?
+            };
+            return Ok(Self {
+                name: spec.into(),
+                api,
+                version: None,
+            });
+        }
+        open_at(root, Some(spec)).map(|crate_| Self::of(spec, &crate_))
+    }
+}
+
+fn print_changes(changes: &[Change]) {
+    for change in changes {
+        outln!("{}: {}: {}", change.level(), change.path(), change.reason());
+        if let Some(old) = change.before() {
+            outln!("  - {}", old.signature());
+        }
+        if let Some(new) = change.after() {
+            outln!("  + {}", new.signature());
+        }
+    }
+}
+
+fn print_summary(changes: &[Change]) {
+    let count = |level: Level| {
+        changes
+            .iter()
+            .filter(|change| change.level() == level)
+            .count()
+    };
+    let (major, minor) = (count(Level::Major), count(Level::Minor));
+    if changes.is_empty() {
+        outln!("No changes to the API");
+    } else {
+        outln!(
+            "{} change{}: {major} major, {minor} minor",
+            changes.len(),
+            if changes.len() == 1 { "" } else { "s" }
+        );
+    }
+}
+
+pub fn run(crate_: &Crate, old: &str, new: Option<&str>) -> i32 {
+    let sides = Side::load(old, &crate_.root).and_then(|old| {
+        let new = match new {
+            Some(new) => Side::load(new, &crate_.root)?,
+            None => Side::of(&crate_.root.display().to_string(), crate_),
+        };
+        Ok((old, new))
+    });
+    let (old, new) = match sides {
+        Ok(sides) => sides,
+        Err(err) => {
+            eprintln!("{err}");
+            return 2;
+        }
+    };
+
+    let changes = Change::between(&old.api, &new.api);
+    print_changes(&changes);
+    if !changes.is_empty() {
+        outln!();
+    }
+    print_summary(&changes);
+
+    // A snapshot has no version; the new side's is the best guess at the
+    // last release.
+    let Some((base_name, base)) = old
+        .version
+        .as_ref()
+        .map(|version| (&old.name, version))
+        .or_else(|| new.version.as_ref().map(|version| (&new.name, version)))
+    else {
+        return 0;
+    };
+    let base_version: Version = match base.parse() {
+        Ok(version) => version,
+        Err(err) => {
+            eprintln!("Could not suggest a version for {base_name}: {err}");
+            return 2;
+        }
+    };
+    let level = semver::level(&changes);
+    let required = base_version.bump(level);
+    outln!("Suggested version: {required} ({level} release after {base})");
+
+    // Only a crate compared with an earlier version of itself has a version
+    // to check. Leaving it unchanged is not enough for any release.
+    let (Some(_), Some(new_version)) = (&old.version, &new.version) else {
+        return 0;
+    };
+    match new_version.parse::<Version>() {
+        Ok(version) if version < required => {
+            outln!(
+                "{} has version {new_version}, which is not enough for a {level} release",
+                new.name
+            );
+            1
+        }
+        Ok(_) => 0,
+        Err(err) => {
+            eprintln!("Could not check the version of {}: {err}", new.name);
+            2
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use thigpen::stats::{Lines, Stats};
+use thigpen::Module;
+
+use super::{written, Crate, Format};
+
+#[derive(serde::Serialize)]
+struct Row<'a> {
+    module: &'a str,
+    lines: Lines,
+    items: &'a BTreeMap<String, usize>,
+    functions: usize,
+    average_complexity: f64,
+    max_complexity: usize,
+}
+
+impl<'a> Row<'a> {
+    fn new(module: &'a str, stats: &'a Stats) -> Self {
+        Self {
+            module,
+            lines: stats.lines(),
+            items: stats.items(),
+            functions: stats.functions(),
+            average_complexity: stats.average_complexity(),
+            max_complexity: stats.max_complexity(),
+        }
+    }
+}
+
+pub fn run(crate_: &Crate, format: Format) -> i32 {
+    fn collect<'a>(module: &'a Module, rows: &mut Vec<Row<'a>>) {
+        rows.push(Row::new(module.usepath(), module.stats()));
+        module
+            .children()
+            .iter()
+            .for_each(|child| collect(child, rows));
+    }
+    let lib = &crate_.lib;
+    let mut rows = vec![Row::new(lib.name(), lib.stats())];
+    lib.modules()
+        .iter()
+        .for_each(|module| collect(module, &mut rows));
+
+    match format {
+        Format::Json => {
+            written(serde_json::to_writer_pretty(
+                std::io::stdout().lock(),
+                &rows,
+            ));
+            outln!();
+        }
+        Format::Table => {
+            let width = rows
+                .iter()
+                .map(|row| row.module.len())
+                .chain(["module".len()])
+                .max()
+                .unwrap_or_default();
+            outln!(
+                "{:width$}  {:>6}  {:>6}  {:>7}  {:>5}  {:>3}  {:>6}  {:>6}  items",
+                "module",
+                "lines",
+                "code",
+                "comment",
+                "blank",
+                "fns",
+                "avg cc",
+                "max cc"
+            );
+            for row in rows.iter() {
+                let items: Vec<String> = row
+                    .items
+                    .iter()
+                    .map(|(kind, count)| format!("{count} {kind}"))
+                    .collect();
+                outln!(
+                    "{:width$}  {:>6}  {:>6}  {:>7}  {:>5}  {:>3}  {:>6.2}  {:>6}  {}",
+                    row.module,
+                    row.lines.total,
+                    row.lines.code,
+                    row.lines.comment,
+                    row.lines.blank,
+                    row.functions,
+                    row.average_complexity,
+                    row.max_complexity,
+                    items.join(", ")
+                );
+            }
+        }
+    }
+    0
+}
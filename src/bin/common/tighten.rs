@@ -0,0 +1,38 @@
+use thigpen::exposure::Suggestion;
+use thigpen::Reference;
+
+use super::{display_path, Crate};
+
+pub fn run(crate_: &Crate, tests: bool) -> i32 {
+    let external: Vec<Reference> = if tests {
+        crate_.references_from_targets()
+    } else {
+        vec![]
+    };
+    let lib = &crate_.lib;
+    let suggestions = Suggestion::of(lib, &external);
+    if suggestions.is_empty() {
+        outln!("No item of {} is more visible than it needs", lib.name());
+        return 0;
+    }
+
+    for suggestion in suggestions.iter() {
+        let usage = suggestion.usage();
+        let item = usage.item();
+        outln!(
+            "{}:{}: {} {} {}::{} can be {}",
+            display_path(crate_, item.file()),
+            item.line(),
+            item.visibility(),
+            item.kind(),
+            usage.module(),
+            item.name(),
+            match suggestion.visibility() {
+                Some(visibility) => visibility.to_string(),
+                None => "private".into(),
+            }
+        );
+    }
+    outln!("{} items can be less visible", suggestions.len());
+    1
+}
@@ -0,0 +1,39 @@
+use thigpen::usage::Usage;
+use thigpen::{Reference, Visibility};
+
+use super::{display_path, Crate};
+
+pub fn run(crate_: &Crate, tests: bool) -> i32 {
+    let external: Vec<Reference> = if tests {
+        crate_.references_from_targets()
+    } else {
+        vec![]
+    };
+    let lib = &crate_.lib;
+    let unused: Vec<Usage> = Usage::of(lib, &external)
+        .into_iter()
+        .filter(|usage| usage.is_unused() && *usage.item().visibility() == Visibility::Public)
+        .collect();
+    if unused.is_empty() {
+        outln!(
+            "Every pub item of {} is used outside its module",
+            lib.name()
+        );
+        return 0;
+    }
+
+    for usage in unused.iter() {
+        let item = usage.item();
+        outln!(
+            "{}:{}: {} {} {}::{}",
+            display_path(crate_, item.file()),
+            item.line(),
+            item.visibility(),
+            item.kind(),
+            usage.module(),
+            item.name()
+        );
+    }
+    outln!("{} unused pub items", unused.len());
+    1
+}
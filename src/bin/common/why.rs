@@ -0,0 +1,36 @@
+use thigpen::graph::ModuleGraph;
+use thigpen::pattern::ModulePattern;
+
+use super::{display_path, Crate};
+
+pub fn run(crate_: &Crate, from: &ModulePattern, to: &ModulePattern) -> i32 {
+    let lib = &crate_.lib;
+    let graph = ModuleGraph::new(lib);
+    for pattern in [from, to] {
+        if !graph
+            .modules()
+            .iter()
+            .any(|module| pattern.matches(module, lib.name()))
+        {
+            eprintln!("No module of {} matches {}", lib.name(), pattern);
+            return 2;
+        }
+    }
+
+    let Some(path) = graph.shortest_path(from, to) else {
+        outln!("{} does not depend on {}", from, to);
+        return 1;
+    };
+    for dependency in path {
+        let reference = dependency.reference();
+        outln!("{} -> {}", dependency.from(), dependency.to());
+        outln!(
+            "  {}:{}: {} {}",
+            display_path(crate_, reference.file()),
+            reference.line(),
+            reference.kind(),
+            reference.written()
+        );
+    }
+    0
+}
@@ -0,0 +1,171 @@
+//! Conditional compilation: `#[cfg]` and `#[cfg_attr]` predicates.
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+
+/// A configuration predicate, as in `#[cfg(all(feature = "net", unix))]`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cfg {
+    /// `feature = "name"`
+    Feature(String),
+    Not(Box<Cfg>),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    /// Any other predicate, like `unix` or `target_os = "linux"`, as written.
+    Other(String),
+}
+
+impl Cfg {
+    fn from_meta(meta: &syn::Meta) -> Self {
+        let nested = |list: &syn::MetaList| -> Vec<Cfg> {
+            list.parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+                .map(|metas| metas.iter().map(Cfg::from_meta).collect())
+                .unwrap_or_default()
+        };
+        match meta {
+            syn::Meta::NameValue(name_value) if name_value.path.is_ident("feature") => {
+                match &name_value.value {
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(feature),
+                        ..
+                    }) => Cfg::Feature(feature.value()),
+                    _ => Cfg::Other(quote_meta(meta)),
+                }
+            }
+            syn::Meta::List(list) if list.path.is_ident("not") => {
+                match nested(list).into_iter().next() {
+                    Some(cfg) => Cfg::Not(Box::new(cfg)),
+                    None => Cfg::Other(quote_meta(meta)),
+                }
+            }
+            syn::Meta::List(list) if list.path.is_ident("all") => Cfg::All(nested(list)),
+            syn::Meta::List(list) if list.path.is_ident("any") => Cfg::Any(nested(list)),
+            _ => Cfg::Other(quote_meta(meta)),
+        }
+    }
+
+    /// The features that have to be enabled for the predicate to hold.
+    pub fn required_features(&self) -> Vec<&str> {
+        match self {
+            Cfg::Feature(feature) => vec![feature],
+            Cfg::Not(cfg) => cfg.excluded_features(),
+            Cfg::All(cfgs) => cfgs.iter().flat_map(Cfg::required_features).collect(),
+            // Each of them could be enabled instead; that's for `any` to
+            // decide, not a single feature.
+            Cfg::Any(_) | Cfg::Other(_) => vec![],
+        }
+    }
+
+    /// The features that have to be disabled for the predicate to hold.
+    pub fn excluded_features(&self) -> Vec<&str> {
+        match self {
+            Cfg::Not(cfg) => cfg.sufficient_features(),
+            Cfg::All(cfgs) => cfgs.iter().flat_map(Cfg::excluded_features).collect(),
+            Cfg::Any(cfgs) => match cfgs.split_first() {
+                Some((first, rest)) => first
+                    .excluded_features()
+                    .into_iter()
+                    .filter(|feature| {
+                        rest.iter()
+                            .all(|cfg| cfg.excluded_features().contains(feature))
+                    })
+                    .collect(),
+                None => vec![],
+            },
+            Cfg::Feature(_) | Cfg::Other(_) => vec![],
+        }
+    }
+
+    /// The features that make the predicate hold just by being enabled.
+    fn sufficient_features(&self) -> Vec<&str> {
+        match self {
+            Cfg::Feature(feature) => vec![feature],
+            Cfg::Any(cfgs) => cfgs.iter().flat_map(Cfg::sufficient_features).collect(),
+            Cfg::All(cfgs) if cfgs.len() == 1 => cfgs[0].sufficient_features(),
+            Cfg::All(_) | Cfg::Not(_) | Cfg::Other(_) => vec![],
+        }
+    }
+
+    /// Every feature the predicate mentions.
+    pub fn features(&self) -> Vec<&str> {
+        match self {
+            Cfg::Feature(feature) => vec![feature],
+            Cfg::Not(cfg) => cfg.features(),
+            Cfg::All(cfgs) | Cfg::Any(cfgs) => cfgs.iter().flat_map(Cfg::features).collect(),
+            Cfg::Other(_) => vec![],
+        }
+    }
+}
+
+impl std::fmt::Display for Cfg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |cfgs: &[Cfg]| {
+            cfgs.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Cfg::Feature(feature) => write!(f, "feature = {:?}", feature),
+            Cfg::Not(cfg) => write!(f, "not({})", cfg),
+            Cfg::All(cfgs) => write!(f, "all({})", list(cfgs)),
+            Cfg::Any(cfgs) => write!(f, "any({})", list(cfgs)),
+            Cfg::Other(other) => write!(f, "{}", other),
+        }
+    }
+}
+
+fn quote_meta(meta: &syn::Meta) -> String {
+    // proc_macro2 puts spaces between every token; `target_os = "linux"` is
+    // about as complicated as these get, so that is good enough.
+    meta.to_token_stream().to_string()
+}
+
+/// The predicates of the `#[cfg]` attributes in `attrs`, and of the
+/// `#[cfg_attr]` attributes.
+pub(crate) fn conditions(attrs: &[syn::Attribute]) -> (Vec<Cfg>, Vec<Cfg>) {
+    let mut cfg = vec![];
+    let mut cfg_attr = vec![];
+    for attr in attrs {
+        let syn::Meta::List(list) = &attr.meta else {
+            continue;
+        };
+        let target = if list.path.is_ident("cfg") {
+            &mut cfg
+        } else if list.path.is_ident("cfg_attr") {
+            &mut cfg_attr
+        } else {
+            continue;
+        };
+        if let Some(meta) = list
+            .parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+            .ok()
+            .and_then(|metas| metas.into_iter().next())
+        {
+            target.push(Cfg::from_meta(&meta));
+        }
+    }
+    (cfg, cfg_attr)
+}
+
+/// The attributes of `item`.
+pub(crate) fn attrs(item: &syn::Item) -> &[syn::Attribute] {
+    match item {
+        syn::Item::Const(item) => &item.attrs,
+        syn::Item::Enum(item) => &item.attrs,
+        syn::Item::ExternCrate(item) => &item.attrs,
+        syn::Item::Fn(item) => &item.attrs,
+        syn::Item::ForeignMod(item) => &item.attrs,
+        syn::Item::Impl(item) => &item.attrs,
+        syn::Item::Macro(item) => &item.attrs,
+        syn::Item::Mod(item) => &item.attrs,
+        syn::Item::Static(item) => &item.attrs,
+        syn::Item::Struct(item) => &item.attrs,
+        syn::Item::Trait(item) => &item.attrs,
+        syn::Item::TraitAlias(item) => &item.attrs,
+        syn::Item::Type(item) => &item.attrs,
+        syn::Item::Union(item) => &item.attrs,
+        syn::Item::Use(item) => &item.attrs,
+        _ => &[],
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::{Path, PathBuf};
+
+/// What is wrong with the files of a module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticKind {
+    /// Neither `foo.rs` nor `foo/mod.rs` exists.
+    MissingFile,
+    /// Both `foo.rs` and `foo/mod.rs` exist, which rustc refuses (E0761).
+    /// Only `foo.rs` is read.
+    AmbiguousFile,
+}
+
+/// A problem with finding the file of a module, at the `mod` declaration
+/// that has it.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Diagnostic {
+    kind: DiagnosticKind,
+    usepath: String,
+    file: Box<Path>,
+    line: usize,
+    /// `foo/mod.rs` and `foo.rs`, in that order.
+    candidates: [PathBuf; 2],
+}
+
+impl Diagnostic {
+    pub(crate) fn new(
+        kind: DiagnosticKind,
+        usepath: &str,
+        file: &Path,
+        line: usize,
+        candidates: &[PathBuf; 2],
+    ) -> Self {
+        Self {
+            kind,
+            usepath: usepath.into(),
+            file: file.into(),
+            line,
+            candidates: candidates.clone(),
+        }
+    }
+
+    pub fn kind(&self) -> DiagnosticKind {
+        self.kind
+    }
+
+    /// The `usepath` of the declared module.
+    pub fn usepath(&self) -> &str {
+        &self.usepath
+    }
+
+    /// The file holding the `mod` declaration.
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// The line of the `mod` declaration, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The files the module could be in: `foo/mod.rs`, then `foo.rs`.
+    pub fn candidates(&self) -> &[PathBuf; 2] {
+        &self.candidates
+    }
+}
@@ -0,0 +1,187 @@
+//! Items that are more visible than they need to be.
+//!
+//! Items reachable from the crate root through `pub` modules and `pub use`
+//! re-exports are the crate's API, and so are items referred to from
+//! outside the crate; those are left alone. Every other item only needs to
+//! be visible to the modules that refer to it: a private item is visible to
+//! its module and the modules beneath it, and a `pub(super)` item to its
+//! parent module and the modules beneath that.
+use std::collections::{HashMap, HashSet};
+
+use crate::usage::Usage;
+use crate::{
+    Lib, Module, PublicIdentifier, PublicIdentifierType, Reference, ReferenceKind, Visibility,
+};
+
+/// An item that could be less visible.
+#[derive(Debug)]
+pub struct Suggestion<'a> {
+    usage: Usage<'a>,
+    visibility: Option<Visibility>,
+}
+
+impl<'a> Suggestion<'a> {
+    /// A suggestion for every item in `lib`'s interfaces that is more visible
+    /// than the references to it need. `external` holds references from
+    /// outside the crate, as for [`Usage::of`].
+    pub fn of(lib: &'a Lib, external: &'a [Reference]) -> Vec<Self> {
+        let reachable = reachable(lib);
+        Usage::of(lib, external)
+            .into_iter()
+            .filter(|usage| {
+                let module = usage.module().to_string();
+                let is_reachable = reachable
+                    .contains(&(module.clone(), usage.item().name().into()))
+                    || (*usage.item().visibility() == Visibility::Public
+                        && reachable.contains(&(module, "*".into())));
+                usage.external().is_empty() && !is_reachable
+            })
+            .filter_map(|usage| {
+                let current = rank(Some(usage.item().visibility()))?;
+                let visibility = narrowest(&usage, lib.name());
+                (rank(visibility.as_ref())? < current).then_some(Suggestion { usage, visibility })
+            })
+            .collect()
+    }
+
+    /// The item, and where it is used from.
+    pub fn usage(&self) -> &Usage<'a> {
+        &self.usage
+    }
+
+    /// The narrowest visibility the item needs, or `None` if it can be
+    /// private.
+    pub fn visibility(&self) -> Option<&Visibility> {
+        self.visibility.as_ref()
+    }
+}
+
+/// How visible an item is; `pub(in path)` is too hard to compare, and has
+/// no rank.
+fn rank(visibility: Option<&Visibility>) -> Option<u8> {
+    match visibility {
+        None | Some(Visibility::SelfOnly) => Some(0),
+        Some(Visibility::Super) => Some(1),
+        Some(Visibility::Crate) => Some(2),
+        Some(Visibility::Public) => Some(3),
+        Some(Visibility::In(_)) => None,
+    }
+}
+
+/// Whether the module at `usepath` is `ancestor` or beneath it.
+fn is_within(usepath: &str, ancestor: &str) -> bool {
+    usepath == ancestor
+        || usepath
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+fn narrowest(usage: &Usage, crate_name: &str) -> Option<Visibility> {
+    let module = usage.module();
+    if usage.modules().iter().all(|user| is_within(user, module)) {
+        return None;
+    }
+    match module.rsplit_once("::") {
+        // `pub(super)` in a top-level module is the same as `pub(crate)`.
+        Some((parent, _)) if parent != crate_name => {
+            if usage.modules().iter().all(|user| is_within(user, parent)) {
+                Some(Visibility::Super)
+            } else {
+                Some(Visibility::Crate)
+            }
+        }
+        _ => Some(Visibility::Crate),
+    }
+}
+
+/// The `(module, name)` of every item reachable from the crate root. A name
+/// of `*` means every `pub` item of the module, as re-exported by a glob.
+fn reachable(lib: &Lib) -> HashSet<(String, String)> {
+    fn collect<'a>(
+        usepath: &'a str,
+        interface: &'a [PublicIdentifier],
+        references: &'a [Reference],
+        children: &'a [Module],
+        reachable: &mut HashSet<(String, String)>,
+        reexports: &mut Vec<&'a [String]>,
+    ) {
+        for item in interface
+            .iter()
+            .filter(|item| *item.visibility() == Visibility::Public)
+        {
+            reachable.insert((usepath.into(), item.name().into()));
+            if item.kind() == PublicIdentifierType::Use {
+                // The `use` declaration that this re-export comes from.
+                reexports.extend(
+                    references
+                        .iter()
+                        .filter(|reference| {
+                            reference.kind() == ReferenceKind::Use
+                                && reference.file() == item.file()
+                                && reference.line() == item.line()
+                        })
+                        .map(Reference::path),
+                );
+            }
+            if item.kind() == PublicIdentifierType::Mod {
+                if let Some(child) = children.iter().find(|child| child.name() == item.name()) {
+                    collect(
+                        child.usepath(),
+                        child.interface(),
+                        child.references(),
+                        child.children(),
+                        reachable,
+                        reexports,
+                    );
+                }
+            }
+        }
+    }
+    fn index<'a>(modules: &'a [Module], by_usepath: &mut HashMap<&'a str, &'a Module>) {
+        for module in modules {
+            by_usepath.insert(module.usepath(), module);
+            index(module.children(), by_usepath);
+        }
+    }
+    let mut modules = HashMap::new();
+    index(lib.modules(), &mut modules);
+
+    let mut reachable = HashSet::new();
+    let mut reexports = vec![];
+    collect(
+        lib.name(),
+        lib.interface(),
+        lib.references(),
+        lib.modules(),
+        &mut reachable,
+        &mut reexports,
+    );
+
+    // Re-exported items are reachable, wherever they are declared, and so
+    // is everything a re-exported module or glob makes public.
+    let mut collected = HashSet::new();
+    while let Some(path) = reexports.pop() {
+        let Some((name, module)) = path.split_last() else {
+            continue;
+        };
+        let module = module.join("::");
+        let reexported = match name.as_str() {
+            "*" => module.clone(),
+            name => format!("{}::{}", module, name),
+        };
+        reachable.insert((module, name.clone()));
+        if let Some(module) = modules.get(reexported.as_str()) {
+            if collected.insert(module.usepath()) {
+                collect(
+                    module.usepath(),
+                    module.interface(),
+                    module.references(),
+                    module.children(),
+                    &mut reachable,
+                    &mut reexports,
+                );
+            }
+        }
+    }
+    reachable
+}
@@ -0,0 +1,121 @@
+//! What each cargo feature adds to, removes from or changes in a crate,
+//! according to the `#[cfg]` and `#[cfg_attr]` attributes on its items and
+//! `mod` declarations.
+//!
+//! Only the attributes themselves are read: an item in a gated module is
+//! implied by the module, and is not listed again unless it has a `#[cfg]`
+//! of its own.
+use std::collections::BTreeMap;
+
+use crate::cfg::Cfg;
+use crate::{Lib, Module, PublicIdentifier, PublicIdentifierType};
+
+/// A module or identifier gated by a feature.
+#[derive(Clone, Copy, Debug)]
+pub enum Gated<'a> {
+    Module(&'a Module),
+    /// An identifier, along with the `usepath` of its module.
+    Item(&'a str, &'a PublicIdentifier),
+}
+
+/// What enabling one feature does to a crate.
+#[derive(Debug, Default)]
+pub struct Feature<'a> {
+    adds: Vec<Gated<'a>>,
+    removes: Vec<Gated<'a>>,
+    alters: Vec<Gated<'a>>,
+}
+
+impl<'a> Feature<'a> {
+    /// The modules and identifiers that only exist when the feature is
+    /// enabled, e.g. `#[cfg(feature = "net")]`.
+    pub fn adds(&self) -> &[Gated<'a>] {
+        &self.adds
+    }
+
+    /// The modules and identifiers that disappear when the feature is
+    /// enabled, e.g. `#[cfg(not(feature = "net"))]`.
+    pub fn removes(&self) -> &[Gated<'a>] {
+        &self.removes
+    }
+
+    /// The modules and identifiers that get extra attributes when the feature
+    /// is enabled, e.g. `#[cfg_attr(feature = "serde", derive(Serialize))]`.
+    pub fn alters(&self) -> &[Gated<'a>] {
+        &self.alters
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.adds.is_empty() && self.removes.is_empty() && self.alters.is_empty()
+    }
+}
+
+/// Every feature a crate's `#[cfg]` and `#[cfg_attr]` attributes mention,
+/// and what each one does.
+#[derive(Debug, Default)]
+pub struct FeatureMap<'a> {
+    features: BTreeMap<&'a str, Feature<'a>>,
+}
+
+impl<'a> FeatureMap<'a> {
+    pub fn of(lib: &'a Lib) -> Self {
+        let mut map = Self::default();
+        map.add_items(lib.name(), lib.interface());
+        lib.modules()
+            .iter()
+            .for_each(|module| map.add_module(module));
+        map
+    }
+
+    /// The features mentioned, sorted by name, and what each one does.
+    pub fn features(&self) -> impl Iterator<Item = (&'a str, &Feature<'a>)> {
+        self.features.iter().map(|(name, feature)| (*name, feature))
+    }
+
+    /// What the feature `name` does, or `None` if no attribute mentions it.
+    pub fn get(&self, name: &str) -> Option<&Feature<'a>> {
+        self.features.get(name)
+    }
+
+    fn add(&mut self, gated: Gated<'a>, cfg: &'a [Cfg], cfg_attr: &'a [Cfg]) {
+        for cfg in cfg {
+            // A feature only mentioned in an `any` still gets an entry.
+            for feature in cfg.features() {
+                self.features.entry(feature).or_default();
+            }
+            for feature in cfg.required_features() {
+                self.features.entry(feature).or_default().adds.push(gated);
+            }
+            for feature in cfg.excluded_features() {
+                self.features
+                    .entry(feature)
+                    .or_default()
+                    .removes
+                    .push(gated);
+            }
+        }
+        let mut altering: Vec<&str> = cfg_attr.iter().flat_map(Cfg::features).collect();
+        altering.sort();
+        altering.dedup();
+        for feature in altering {
+            self.features.entry(feature).or_default().alters.push(gated);
+        }
+    }
+
+    fn add_items(&mut self, usepath: &'a str, interface: &'a [PublicIdentifier]) {
+        interface
+            .iter()
+            // The module itself stands in for its `mod` item.
+            .filter(|item| item.kind() != PublicIdentifierType::Mod)
+            .for_each(|item| self.add(Gated::Item(usepath, item), item.cfg(), item.cfg_attr()));
+    }
+
+    fn add_module(&mut self, module: &'a Module) {
+        self.add(Gated::Module(module), module.cfg(), module.cfg_attr());
+        self.add_items(module.usepath(), module.interface());
+        module
+            .children()
+            .iter()
+            .for_each(|child| self.add_module(child));
+    }
+}
@@ -0,0 +1,165 @@
+//! Pruning a crate down to the part of it worth looking at.
+use crate::pattern::ModulePattern;
+use crate::{Lib, Module, PublicIdentifier, PublicIdentifierType};
+
+/// What to keep of a crate. By default, everything is kept.
+#[derive(Clone, Debug, Default)]
+pub struct Filter {
+    root: Option<String>,
+    depth: Option<usize>,
+    include: Vec<ModulePattern>,
+    exclude: Vec<ModulePattern>,
+    kinds: Option<Vec<PublicIdentifierType>>,
+}
+
+/// The reason a filter could not be applied.
+#[derive(Debug)]
+pub enum FilterError {
+    /// There is no module at the `usepath` given as the root.
+    RootNotFound(String),
+}
+
+impl std::fmt::Display for FilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterError::RootNotFound(root) => write!(f, "there is no module {}", root),
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+impl Filter {
+    /// Keep only the module at `usepath` and the modules beneath it, with
+    /// that module in place of the crate root. A leading `crate` stands for
+    /// the crate's name, as in `crate::net`.
+    pub fn root(mut self, usepath: &str) -> Self {
+        self.root = Some(usepath.into());
+        self
+    }
+
+    /// Keep only modules at most `depth` levels beneath the root.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Keep only modules matching one of `patterns`, along with the modules
+    /// above them. Without any patterns, every module is kept.
+    pub fn include(mut self, patterns: Vec<ModulePattern>) -> Self {
+        self.include = patterns;
+        self
+    }
+
+    /// Drop modules matching one of `patterns`, and the modules beneath
+    /// them.
+    pub fn exclude(mut self, patterns: Vec<ModulePattern>) -> Self {
+        self.exclude = patterns;
+        self
+    }
+
+    /// Keep only the public identifiers of these kinds. Those naming modules
+    /// (`pub mod`) go with the modules themselves instead.
+    pub fn kinds(mut self, kinds: Vec<PublicIdentifierType>) -> Self {
+        self.kinds = Some(kinds);
+        self
+    }
+
+    /// Prune `lib` down to what the filter keeps.
+    pub fn apply(&self, lib: Lib) -> Result<Lib, FilterError> {
+        let crate_name = lib.name.clone();
+        let mut lib = match &self.root {
+            None => lib,
+            Some(root) => {
+                let usepath = match root.strip_prefix("crate") {
+                    Some(rest) if rest.is_empty() || rest.starts_with("::") => {
+                        format!("{}{}", crate_name, rest)
+                    }
+                    _ => root.clone(),
+                };
+                if usepath == crate_name {
+                    lib
+                } else {
+                    let Lib {
+                        path,
+                        modules,
+                        external_crates,
+                        ..
+                    } = lib;
+                    let module = find(modules, &usepath)
+                        .ok_or_else(|| FilterError::RootNotFound(root.clone()))?;
+                    Lib {
+                        name: module.usepath,
+                        path,
+                        interface: module.interface,
+                        modules: module.children,
+                        references: module.references,
+                        impls: module.impls,
+                        stats: module.stats,
+                        external_crates,
+                    }
+                }
+            }
+        };
+
+        lib.modules = lib
+            .modules
+            .into_iter()
+            .filter_map(|module| self.prune(module, 1, &crate_name))
+            .collect();
+        self.filter_interface(&mut lib.interface, &lib.modules);
+        Ok(lib)
+    }
+
+    /// Drop the identifiers of the wrong kinds from `interface`, and those
+    /// naming modules that are not among `children`.
+    fn filter_interface(&self, interface: &mut Vec<PublicIdentifier>, children: &[Module]) {
+        interface.retain(|item| match item.kind() {
+            PublicIdentifierType::Mod => children.iter().any(|child| child.name == item.name),
+            kind => self
+                .kinds
+                .as_ref()
+                .is_none_or(|kinds| kinds.contains(&kind)),
+        });
+    }
+
+    /// Prune `module`, which is `depth` levels beneath the root.
+    fn prune(&self, mut module: Module, depth: usize, crate_name: &str) -> Option<Module> {
+        if self.depth.is_some_and(|max| depth > max)
+            || self
+                .exclude
+                .iter()
+                .any(|pattern| pattern.matches(&module.usepath, crate_name))
+        {
+            return None;
+        }
+        module.children = module
+            .children
+            .into_iter()
+            .filter_map(|child| self.prune(child, depth + 1, crate_name))
+            .collect();
+        let included = self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| pattern.matches(&module.usepath, crate_name));
+        if !included && module.children.is_empty() {
+            return None;
+        }
+        self.filter_interface(&mut module.interface, &module.children);
+        Some(module)
+    }
+}
+
+/// Take the module at `usepath` out of `modules` and their descendants.
+fn find(modules: Vec<Module>, usepath: &str) -> Option<Module> {
+    modules.into_iter().find_map(|module| {
+        if module.usepath == usepath {
+            Some(module)
+        } else if usepath.starts_with(&format!("{}::", module.usepath)) {
+            find(module.children, usepath)
+        } else {
+            None
+        }
+    })
+}
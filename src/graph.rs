@@ -0,0 +1,335 @@
+//! The dependency graph between the modules of a crate.
+//!
+//! A module depends on another module when it refers to a path inside it,
+//! either through a `use` declaration or a path like `crate::net::connect()`.
+//! Declaring a child module with `mod` does not count as a dependency.
+//!
+//! A child referring to its parent, e.g. through `super::Error`, is a
+//! dependency like any other, so a parent re-exporting from its child and
+//! the child using the parent's items form a cycle. Use
+//! [`ModuleGraph::without_ancestors`] to leave those references out.
+use std::collections::{HashMap, VecDeque};
+
+use crate::pattern::ModulePattern;
+use crate::{Lib, Module, Reference};
+
+/// A dependency of one module on another, and the reference that creates it.
+#[derive(Clone, Copy, Debug)]
+pub struct Dependency<'a> {
+    from: &'a str,
+    to: &'a str,
+    reference: &'a Reference,
+}
+
+impl<'a> Dependency<'a> {
+    /// The `usepath` of the depending module.
+    pub fn from(&self) -> &'a str {
+        self.from
+    }
+
+    /// The `usepath` of the module depended upon, or the name of the crate
+    /// for a dependency on another crate.
+    pub fn to(&self) -> &'a str {
+        self.to
+    }
+
+    pub fn reference(&self) -> &'a Reference {
+        self.reference
+    }
+}
+
+/// A set of modules that all depend on each other, directly or indirectly.
+#[derive(Debug)]
+pub struct Cycle<'a> {
+    modules: Vec<&'a str>,
+    dependencies: Vec<Dependency<'a>>,
+}
+
+impl<'a> Cycle<'a> {
+    /// The `usepath`s of the modules in the cycle, sorted.
+    pub fn modules(&self) -> &[&'a str] {
+        &self.modules
+    }
+
+    /// The dependencies between the modules in the cycle.
+    pub fn dependencies(&self) -> &[Dependency<'a>] {
+        &self.dependencies
+    }
+}
+
+/// The modules of a crate and the dependencies between them, along with
+/// their dependencies on other crates.
+pub struct ModuleGraph<'a> {
+    /// The `usepath` of every module, starting with the crate root.
+    modules: Vec<&'a str>,
+    dependencies: Vec<Dependency<'a>>,
+    external: Vec<Dependency<'a>>,
+}
+
+impl<'a> ModuleGraph<'a> {
+    pub fn new(lib: &'a Lib) -> Self {
+        fn collect<'a>(module: &'a Module, modules: &mut Vec<(&'a str, &'a [Reference])>) {
+            modules.push((module.usepath(), module.references()));
+            module
+                .children()
+                .iter()
+                .for_each(|child| collect(child, modules));
+        }
+        let mut modules = vec![(lib.name(), lib.references())];
+        lib.modules()
+            .iter()
+            .for_each(|module| collect(module, &mut modules));
+
+        let usepaths: HashMap<&str, &'a str> = modules
+            .iter()
+            .map(|(usepath, _)| (*usepath, *usepath))
+            .collect();
+        let dependencies = modules
+            .iter()
+            .flat_map(|(from, references)| {
+                let usepaths = &usepaths;
+                references.iter().filter_map(move |reference| {
+                    // The module a path points into is the longest prefix of
+                    // it that is a module.
+                    let to = (1..=reference.path().len()).rev().find_map(|length| {
+                        usepaths
+                            .get(reference.path()[..length].join("::").as_str())
+                            .copied()
+                    })?;
+                    (to != *from).then_some(Dependency {
+                        from,
+                        to,
+                        reference,
+                    })
+                })
+            })
+            .collect();
+
+        // Paths into other crates are left as written, so they start with
+        // the crate's name.
+        let external = modules
+            .iter()
+            .flat_map(|(from, references)| {
+                references.iter().filter_map(move |reference| {
+                    let to = lib
+                        .external_crates()
+                        .iter()
+                        .find(|name| reference.path().first() == Some(*name))?;
+                    Some(Dependency {
+                        from,
+                        to,
+                        reference,
+                    })
+                })
+            })
+            .collect();
+
+        Self {
+            modules: modules.into_iter().map(|(usepath, _)| usepath).collect(),
+            dependencies,
+            external,
+        }
+    }
+
+    /// The `usepath` of the crate root, i.e. the crate's name.
+    pub fn root(&self) -> &'a str {
+        self.modules[0]
+    }
+
+    /// The `usepath` of every module, starting with the crate root.
+    pub fn modules(&self) -> &[&'a str] {
+        &self.modules
+    }
+
+    /// Every dependency between two modules. There is one per reference, so
+    /// a module can depend on another more than once.
+    pub fn dependencies(&self) -> &[Dependency<'a>] {
+        &self.dependencies
+    }
+
+    /// Every dependency of a module on one of the crate's
+    /// [external crates](Lib::external_crates). `to` is the name of the
+    /// external crate.
+    pub fn external_dependencies(&self) -> &[Dependency<'a>] {
+        &self.external
+    }
+
+    /// `modules`, followed by every module that depends on one of them,
+    /// directly or indirectly, nearest first.
+    pub fn dependents(&self, modules: &[&'a str]) -> Vec<&'a str> {
+        let mut dependents = modules.to_vec();
+        let mut next = 0;
+        while let Some(module) = dependents.get(next).copied() {
+            next += 1;
+            for dependency in self
+                .dependencies
+                .iter()
+                .filter(|dependency| dependency.to == module)
+            {
+                if !dependents.contains(&dependency.from) {
+                    dependents.push(dependency.from);
+                }
+            }
+        }
+        dependents
+    }
+
+    /// The shortest chain of dependencies from a module matching `from` to a
+    /// module matching `to`, or `None` if there is none. Each step is the
+    /// first dependency found between its two modules.
+    pub fn shortest_path(
+        &self,
+        from: &ModulePattern,
+        to: &ModulePattern,
+    ) -> Option<Vec<Dependency<'a>>> {
+        let root = self.root();
+        // The dependency each module was first reached through.
+        let mut reached: HashMap<&str, Option<Dependency>> = HashMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        for module in self.modules.iter() {
+            if from.matches(module, root) {
+                reached.insert(module, None);
+                queue.push_back(module);
+            }
+        }
+        while let Some(module) = queue.pop_front() {
+            for dependency in self
+                .dependencies
+                .iter()
+                .filter(|dependency| dependency.from == module)
+            {
+                if reached.contains_key(dependency.to) {
+                    continue;
+                }
+                reached.insert(dependency.to, Some(*dependency));
+                if to.matches(dependency.to, root) {
+                    let mut path = vec![*dependency];
+                    while let Some(Some(previous)) = reached.get(path[0].from) {
+                        path.insert(0, *previous);
+                    }
+                    return Some(path);
+                }
+                queue.push_back(dependency.to);
+            }
+        }
+        None
+    }
+
+    /// The same graph without the dependencies of modules on their parent,
+    /// or on any module above it, up to the crate root.
+    pub fn without_ancestors(mut self) -> Self {
+        self.dependencies.retain(|dependency| {
+            !dependency
+                .from
+                .strip_prefix(dependency.to)
+                .is_some_and(|rest| rest.starts_with("::"))
+        });
+        self
+    }
+
+    /// Every set of modules that depend on each other, i.e. every strongly
+    /// connected component of more than one module. A child and a parent
+    /// that refer to each other are a cycle, unless the graph is
+    /// [`without_ancestors`](Self::without_ancestors).
+    pub fn cycles(&self) -> Vec<Cycle<'a>> {
+        let index: HashMap<&str, usize> = self
+            .modules
+            .iter()
+            .enumerate()
+            .map(|(index, usepath)| (*usepath, index))
+            .collect();
+        let mut edges: Vec<Vec<usize>> = vec![vec![]; self.modules.len()];
+        for dependency in self.dependencies.iter() {
+            let (from, to) = (index[dependency.from], index[dependency.to]);
+            if !edges[from].contains(&to) {
+                edges[from].push(to);
+            }
+        }
+
+        let mut tarjan = Tarjan {
+            edges: &edges,
+            index: vec![None; self.modules.len()],
+            lowlink: vec![0; self.modules.len()],
+            on_stack: vec![false; self.modules.len()],
+            stack: vec![],
+            next: 0,
+            components: vec![],
+        };
+        for node in 0..self.modules.len() {
+            if tarjan.index[node].is_none() {
+                tarjan.connect(node);
+            }
+        }
+
+        let mut cycles: Vec<Cycle> = tarjan
+            .components
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .map(|component| {
+                let mut modules: Vec<&str> =
+                    component.iter().map(|node| self.modules[*node]).collect();
+                modules.sort();
+                let dependencies = self
+                    .dependencies
+                    .iter()
+                    .filter(|dependency| {
+                        modules.contains(&dependency.from) && modules.contains(&dependency.to)
+                    })
+                    .copied()
+                    .collect();
+                Cycle {
+                    modules,
+                    dependencies,
+                }
+            })
+            .collect();
+        cycles.sort_by(|a, b| a.modules.cmp(&b.modules));
+        cycles
+    }
+}
+
+/// Tarjan's strongly connected components algorithm.
+struct Tarjan<'a> {
+    edges: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    lowlink: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn connect(&mut self, node: usize) {
+        self.index[node] = Some(self.next);
+        self.lowlink[node] = self.next;
+        self.next += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &next in self.edges[node].iter() {
+            match self.index[next] {
+                None => {
+                    self.connect(next);
+                    self.lowlink[node] = self.lowlink[node].min(self.lowlink[next]);
+                }
+                Some(index) if self.on_stack[next] => {
+                    self.lowlink[node] = self.lowlink[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.lowlink[node]) == self.index[node] {
+            let mut component = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
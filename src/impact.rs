@@ -0,0 +1,103 @@
+//! What a change to some of a crate's files can affect.
+//!
+//! A change to a module's file can affect the module, every module that
+//! depends on it, directly or indirectly, and the interfaces of all of them.
+use std::path::{Path, PathBuf};
+
+use crate::graph::ModuleGraph;
+use crate::{Lib, Module, PublicIdentifier};
+
+/// The modules and public identifiers a change can affect.
+#[derive(Debug)]
+pub struct Impact<'a> {
+    changed: Vec<&'a str>,
+    affected: Vec<&'a str>,
+    items: Vec<(&'a str, &'a PublicIdentifier)>,
+    unknown: Vec<PathBuf>,
+}
+
+impl<'a> Impact<'a> {
+    /// The impact of changing `files`, which may be relative to the current
+    /// directory.
+    pub fn of(lib: &'a Lib, files: &[PathBuf]) -> Self {
+        fn collect<'a>(
+            module: &'a Module,
+            modules: &mut Vec<(&'a str, Option<&'a Path>, &'a [PublicIdentifier])>,
+        ) {
+            modules.push((module.usepath(), module.file(), module.interface()));
+            module
+                .children()
+                .iter()
+                .for_each(|child| collect(child, modules));
+        }
+        let mut modules = vec![(lib.name(), Some(lib.path()), lib.interface())];
+        lib.modules()
+            .iter()
+            .for_each(|module| collect(module, &mut modules));
+
+        let mut changed = vec![];
+        let mut unknown = vec![];
+        for file in files {
+            let file = canonical(file);
+            let mut found = false;
+            for (usepath, _, _) in modules
+                .iter()
+                .filter(|(_, path, _)| path.is_some_and(|path| canonical(path) == file))
+            {
+                found = true;
+                if !changed.contains(usepath) {
+                    changed.push(*usepath);
+                }
+            }
+            if !found {
+                unknown.push(file);
+            }
+        }
+
+        let affected = ModuleGraph::new(lib).dependents(&changed);
+        let items = affected
+            .iter()
+            .flat_map(|affected| {
+                modules
+                    .iter()
+                    .filter(move |(usepath, _, _)| usepath == affected)
+                    .flat_map(|(usepath, _, interface)| {
+                        interface.iter().map(move |item| (*usepath, item))
+                    })
+            })
+            .collect();
+
+        Self {
+            changed,
+            affected,
+            items,
+            unknown,
+        }
+    }
+
+    /// The `usepath`s of the modules whose files changed.
+    pub fn changed(&self) -> &[&'a str] {
+        &self.changed
+    }
+
+    /// The `usepath`s of the changed modules and of every module depending on
+    /// them, nearest first.
+    pub fn affected(&self) -> &[&'a str] {
+        &self.affected
+    }
+
+    /// The identifiers in the interfaces of the affected modules, along with
+    /// the `usepath` of their module.
+    pub fn items(&self) -> &[(&'a str, &'a PublicIdentifier)] {
+        &self.items
+    }
+
+    /// The changed files that are not part of the crate's module tree.
+    pub fn unknown(&self) -> &[PathBuf] {
+        &self.unknown
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
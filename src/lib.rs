@@ -1,8 +1,34 @@
-use std::fmt::Write;
 use std::path::Path;
 
-#[derive(Debug)]
-enum PublicIdentifierType {
+pub mod api;
+pub mod cfg;
+mod diagnostic;
+pub mod exposure;
+pub mod features;
+pub mod filter;
+pub mod graph;
+pub mod impact;
+pub mod metrics;
+pub mod pattern;
+mod reference;
+pub mod render;
+pub mod rules;
+pub mod semver;
+mod signature;
+pub mod source;
+pub mod stats;
+pub mod usage;
+
+use cfg::Cfg;
+pub use diagnostic::{Diagnostic, DiagnosticKind};
+pub use reference::{Reference, ReferenceKind};
+use source::{FileSystem, Source};
+use stats::Stats;
+
+/// The kind of item a public identifier names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PublicIdentifierType {
     Use,
     Const,
     Enum,
@@ -36,367 +62,992 @@ impl std::fmt::Display for PublicIdentifierType {
     }
 }
 
-#[derive(Debug)]
-struct PublicIdentifier {
+/// Parses the names written by `Display`. Names of more than one word can
+/// also be written with `-` or `_`, as in `trait-alias`.
+impl std::str::FromStr for PublicIdentifierType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.replace(['-', '_'], " ").as_str() {
+            "use" => Ok(PublicIdentifierType::Use),
+            "const" => Ok(PublicIdentifierType::Const),
+            "enum" => Ok(PublicIdentifierType::Enum),
+            "extern crate" => Ok(PublicIdentifierType::ExternCrate),
+            "fn" => Ok(PublicIdentifierType::Fn),
+            "mod" => Ok(PublicIdentifierType::Mod),
+            "static" => Ok(PublicIdentifierType::Static),
+            "struct" => Ok(PublicIdentifierType::Struct),
+            "trait" => Ok(PublicIdentifierType::Trait),
+            "trait alias" => Ok(PublicIdentifierType::TraitAlias),
+            "type" => Ok(PublicIdentifierType::Type),
+            "union" => Ok(PublicIdentifierType::Union),
+            _ => Err(format!("unknown item kind {:?}", name)),
+        }
+    }
+}
+
+/// The visibility of a public identifier. Private (inherited) items are not
+/// part of a module's interface, so they have no variant here.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Visibility {
+    Public,
+    Crate,
+    Super,
+    SelfOnly,
+    In(String),
+}
+
+impl Visibility {
+    fn from_syn(vis: &syn::Visibility) -> Option<Self> {
+        match vis {
+            syn::Visibility::Public(_) => Some(Visibility::Public),
+            syn::Visibility::Restricted(restricted) => {
+                let path = restricted
+                    .path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect::<Vec<String>>()
+                    .join("::");
+                Some(match (restricted.in_token.is_some(), path.as_str()) {
+                    (false, "crate") => Visibility::Crate,
+                    (false, "super") => Visibility::Super,
+                    (false, "self") => Visibility::SelfOnly,
+                    _ => Visibility::In(path),
+                })
+            }
+            syn::Visibility::Inherited => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Visibility::Public => write!(f, "pub"),
+            Visibility::Crate => write!(f, "pub(crate)"),
+            Visibility::Super => write!(f, "pub(super)"),
+            Visibility::SelfOnly => write!(f, "pub(self)"),
+            Visibility::In(path) => write!(f, "pub(in {})", path),
+        }
+    }
+}
+
+/// An item that is visible outside of the module declaring it.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct PublicIdentifier {
     r#type: PublicIdentifierType,
     name: String,
+    visibility: Visibility,
+    file: Box<Path>,
+    line: usize,
+    cfg: Vec<Cfg>,
+    cfg_attr: Vec<Cfg>,
+    signature: String,
+    members: Vec<Member>,
+    non_exhaustive: bool,
 }
 
 impl PublicIdentifier {
-    fn from_use(value: &syn::UseTree) -> Vec<Self> {
+    pub fn kind(&self) -> PublicIdentifierType {
+        self.r#type
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
+
+    /// The file the identifier is declared in.
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// The line the identifier is declared on, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The predicates of the identifier's `#[cfg]` attributes, which must
+    /// all hold for it to exist.
+    pub fn cfg(&self) -> &[Cfg] {
+        &self.cfg
+    }
+
+    /// The predicates of the identifier's `#[cfg_attr]` attributes, under
+    /// which it gets extra attributes, like a `derive`.
+    pub fn cfg_attr(&self) -> &[Cfg] {
+        &self.cfg_attr
+    }
+
+    /// The identifier's declaration on one line, without its attributes,
+    /// visibility, body or [members](PublicIdentifier::members), e.g.
+    /// `fn connect(addr: &str) -> Client` or `use crate::net::Client`.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// The variants of an enum, the named fields of a struct or union (of
+    /// any visibility), or the items of a trait.
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// Whether the item is marked `#[non_exhaustive]`.
+    pub fn is_non_exhaustive(&self) -> bool {
+        self.non_exhaustive
+    }
+
+    /// The identifier `name`, declared on `line`, with the conditions and
+    /// markers read from its `attrs`, and no members.
+    fn new(
+        r#type: PublicIdentifierType,
+        name: String,
+        line: usize,
+        visibility: Visibility,
+        file: &Path,
+        attrs: &[syn::Attribute],
+        signature: String,
+    ) -> Self {
+        let (cfg, cfg_attr) = cfg::conditions(attrs);
+        Self {
+            r#type,
+            name,
+            visibility,
+            file: file.into(),
+            line,
+            cfg,
+            cfg_attr,
+            signature,
+            members: vec![],
+            non_exhaustive: attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("non_exhaustive")),
+        }
+    }
+
+    /// The identifier with the variants, fields or items of its declaration.
+    fn with_members(mut self, members: Vec<Member>) -> Self {
+        self.members = members;
+        self
+    }
+
+    /// The identifiers a `use` tree in `item` brings in, each with the path
+    /// before it in `prefix`, like `crate::net::`.
+    fn from_use(
+        value: &syn::UseTree,
+        prefix: &str,
+        visibility: &Visibility,
+        file: &Path,
+        item: &syn::Item,
+    ) -> Vec<Self> {
+        let identifier = |name: String, line: usize, signature: String| {
+            vec![Self::new(
+                PublicIdentifierType::Use,
+                name,
+                line,
+                visibility.clone(),
+                file,
+                cfg::attrs(item),
+                signature,
+            )]
+        };
         match value {
-            syn::UseTree::Path(usepath) => PublicIdentifier::from_use(&usepath.tree),
-            syn::UseTree::Name(name) => vec![Self {
-                name: name.ident.to_string(),
-                r#type: PublicIdentifierType::Use,
-            }],
-            syn::UseTree::Rename(rename) => vec![Self {
-                name: rename.rename.to_string(),
-                r#type: PublicIdentifierType::Use,
-            }],
-            syn::UseTree::Glob(_) => vec![Self {
-                name: "*".into(),
-                r#type: PublicIdentifierType::Use,
-            }],
+            syn::UseTree::Path(usepath) => PublicIdentifier::from_use(
+                &usepath.tree,
+                &format!("{}{}::", prefix, usepath.ident),
+                visibility,
+                file,
+                item,
+            ),
+            // `use foo::{self}` brings in `foo`.
+            syn::UseTree::Name(name) if name.ident == "self" => {
+                let module = prefix.trim_end_matches("::");
+                identifier(
+                    module.rsplit("::").next().unwrap_or(module).into(),
+                    name.ident.span().start().line,
+                    format!("use {}", module),
+                )
+            }
+            syn::UseTree::Name(name) => identifier(
+                name.ident.to_string(),
+                name.ident.span().start().line,
+                format!("use {}{}", prefix, name.ident),
+            ),
+            syn::UseTree::Rename(rename) if rename.ident == "self" => identifier(
+                rename.rename.to_string(),
+                rename.rename.span().start().line,
+                format!("use {} as {}", prefix.trim_end_matches("::"), rename.rename),
+            ),
+            syn::UseTree::Rename(rename) => identifier(
+                rename.rename.to_string(),
+                rename.rename.span().start().line,
+                format!("use {}{} as {}", prefix, rename.ident, rename.rename),
+            ),
+            syn::UseTree::Glob(glob) => identifier(
+                "*".into(),
+                glob.star_token.span.start().line,
+                format!("use {}*", prefix),
+            ),
             syn::UseTree::Group(group) => group
                 .items
                 .iter()
-                .flat_map(PublicIdentifier::from_use)
+                .flat_map(|tree| PublicIdentifier::from_use(tree, prefix, visibility, file, item))
                 .collect(),
         }
     }
 
-    fn find_in_items(items: &[syn::Item]) -> Vec<Self> {
+    fn find_in_items(items: &[syn::Item], file: &Path) -> Vec<Self> {
         items
             .iter()
-            .filter_map(|item| match item {
-                syn::Item::Const(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::Const,
-                        }])
+            .flat_map(|item| {
+                let (r#type, vis, ident) = match item {
+                    syn::Item::Const(inner) => {
+                        (PublicIdentifierType::Const, &inner.vis, &inner.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::Enum(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::Enum,
-                        }])
+                    syn::Item::Enum(inner) => {
+                        (PublicIdentifierType::Enum, &inner.vis, &inner.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::ExternCrate(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::ExternCrate,
-                        }])
+                    syn::Item::ExternCrate(inner) => {
+                        (PublicIdentifierType::ExternCrate, &inner.vis, &inner.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::Fn(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.sig.ident.to_string(),
-                            r#type: PublicIdentifierType::Fn,
-                        }])
+                    syn::Item::Fn(inner) => {
+                        (PublicIdentifierType::Fn, &inner.vis, &inner.sig.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::ForeignMod(_) => None,
-                syn::Item::Impl(_) => None,
-                syn::Item::Macro(_) => None,
-                syn::Item::Mod(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::Mod,
-                        }])
+                    syn::Item::Mod(inner) => (PublicIdentifierType::Mod, &inner.vis, &inner.ident),
+                    syn::Item::Static(inner) => {
+                        (PublicIdentifierType::Static, &inner.vis, &inner.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::Static(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::Static,
-                        }])
+                    syn::Item::Struct(inner) => {
+                        (PublicIdentifierType::Struct, &inner.vis, &inner.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::Struct(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::Struct,
-                        }])
+                    syn::Item::Trait(inner) => {
+                        (PublicIdentifierType::Trait, &inner.vis, &inner.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::Trait(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::Trait,
-                        }])
+                    syn::Item::TraitAlias(inner) => {
+                        (PublicIdentifierType::TraitAlias, &inner.vis, &inner.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::TraitAlias(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::TraitAlias,
-                        }])
+                    syn::Item::Type(inner) => {
+                        (PublicIdentifierType::Type, &inner.vis, &inner.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::Type(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::Type,
-                        }])
+                    syn::Item::Union(inner) => {
+                        (PublicIdentifierType::Union, &inner.vis, &inner.ident)
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::Union(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(vec![PublicIdentifier {
-                            name: item.ident.to_string(),
-                            r#type: PublicIdentifierType::Union,
-                        }])
+                    syn::Item::Use(inner) => {
+                        let prefix = if inner.leading_colon.is_some() {
+                            "::"
+                        } else {
+                            ""
+                        };
+                        return Visibility::from_syn(&inner.vis)
+                            .map(|visibility| {
+                                PublicIdentifier::from_use(
+                                    &inner.tree,
+                                    prefix,
+                                    &visibility,
+                                    file,
+                                    item,
+                                )
+                            })
+                            .unwrap_or_default();
                     }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::Use(item) => match &item.vis {
-                    syn::Visibility::Public(_) | syn::Visibility::Restricted(_) => {
-                        Some(PublicIdentifier::from_use(&item.tree))
-                    }
-                    syn::Visibility::Inherited => None,
-                },
-                syn::Item::Verbatim(_) => None,
+                    // Foreign modules, impls, macros and verbatim items
+                    // declare nothing that can be named.
+                    _ => return vec![],
+                };
+                Visibility::from_syn(vis)
+                    .map(|visibility| {
+                        PublicIdentifier::new(
+                            r#type,
+                            ident.to_string(),
+                            ident.span().start().line,
+                            visibility,
+                            file,
+                            cfg::attrs(item),
+                            signature::of(item).unwrap_or_default(),
+                        )
+                        .with_members(signature::members(item))
+                    })
+                    .into_iter()
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// What a member of an item is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MemberKind {
+    /// A variant of an enum.
+    Variant,
+    /// A named field of a struct or union.
+    Field,
+    /// An item of a trait that implementations must provide.
+    Required,
+    /// An item of a trait with a default.
+    Provided,
+}
+
+/// A variant of an enum, a named field of a struct or union, or an item of
+/// a trait.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct Member {
+    kind: MemberKind,
+    name: String,
+    visibility: Option<Visibility>,
+    signature: String,
+    line: usize,
+}
+
+impl Member {
+    pub fn kind(&self) -> MemberKind {
+        self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The member's visibility, or `None` if it is private. Variants and
+    /// trait items are as visible as their item.
+    pub fn visibility(&self) -> Option<&Visibility> {
+        self.visibility.as_ref()
+    }
+
+    /// The member's declaration on one line, without its attributes or
+    /// visibility, e.g. `Connected(Client)` or `fn close(&mut self)`. The
+    /// default of a trait item is shown as `{ .. }` or `= ..`.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// The line the member is declared on, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+/// An `impl` block, or a type's `#[derive]` of a trait, which stands for one.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Impl {
+    type_name: String,
+    r#trait: Option<String>,
+    signature: String,
+    items: Vec<PublicIdentifier>,
+    file: Box<Path>,
+    line: usize,
+    cfg: Vec<Cfg>,
+}
+
+impl Impl {
+    /// The name of the type the impl is for, without its path or generics,
+    /// e.g. `Client` for `impl<'a> From<&'a str> for net::Client<'a>`.
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// The trait implemented, as written, or `None` for an inherent impl.
+    pub fn r#trait(&self) -> Option<&str> {
+        self.r#trait.as_deref()
+    }
+
+    /// The impl on one line without its items, e.g. `impl Display for
+    /// Client`, or the derive, e.g. `#[derive(Debug)]`.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// The items of an inherent impl that are visible outside the module
+    /// declaring it. The items of a trait impl are the trait's.
+    pub fn items(&self) -> &[PublicIdentifier] {
+        &self.items
+    }
+
+    /// The file the impl is declared in.
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// The line the impl is declared on, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The predicates of the impl's `#[cfg]` attributes, or of the type's
+    /// for a derive.
+    pub fn cfg(&self) -> &[Cfg] {
+        &self.cfg
+    }
+
+    fn from_impl(item: &syn::ItemImpl, file: &Path) -> Option<Self> {
+        fn type_name(ty: &syn::Type) -> Option<String> {
+            match ty {
+                syn::Type::Path(path) => path
+                    .path
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string()),
+                syn::Type::Reference(reference) => type_name(&reference.elem),
+                syn::Type::Group(group) => type_name(&group.elem),
+                syn::Type::Paren(paren) => type_name(&paren.elem),
                 _ => None,
+            }
+        }
+        let (cfg, _) = cfg::conditions(&item.attrs);
+        let items = match item.trait_ {
+            Some(_) => vec![],
+            None => item
+                .items
+                .iter()
+                .filter_map(|impl_item| {
+                    let (r#type, ident, vis, attrs) = match impl_item {
+                        syn::ImplItem::Const(item) => (
+                            PublicIdentifierType::Const,
+                            &item.ident,
+                            &item.vis,
+                            &item.attrs,
+                        ),
+                        syn::ImplItem::Fn(item) => (
+                            PublicIdentifierType::Fn,
+                            &item.sig.ident,
+                            &item.vis,
+                            &item.attrs,
+                        ),
+                        syn::ImplItem::Type(item) => (
+                            PublicIdentifierType::Type,
+                            &item.ident,
+                            &item.vis,
+                            &item.attrs,
+                        ),
+                        _ => return None,
+                    };
+                    Some(PublicIdentifier::new(
+                        r#type,
+                        ident.to_string(),
+                        ident.span().start().line,
+                        Visibility::from_syn(vis)?,
+                        file,
+                        attrs,
+                        signature::of_impl_item(impl_item)?,
+                    ))
+                })
+                .collect(),
+        };
+        Some(Self {
+            type_name: type_name(&item.self_ty)?,
+            r#trait: item.trait_.as_ref().map(|(bang, path, _)| {
+                format!(
+                    "{}{}",
+                    if bang.is_some() { "!" } else { "" },
+                    signature::of_path(path)
+                )
+            }),
+            signature: signature::of_impl(item),
+            items,
+            file: file.into(),
+            line: item.impl_token.span.start().line,
+            cfg,
+        })
+    }
+
+    /// The impls standing for the `#[derive]` attributes of the type `ident`.
+    fn from_derives(ident: &syn::Ident, attrs: &[syn::Attribute], file: &Path) -> Vec<Self> {
+        let (cfg, _) = cfg::conditions(attrs);
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("derive"))
+            .filter_map(|attr| {
+                attr.parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+                )
+                .ok()
+                .map(|paths| (attr, paths))
+            })
+            .flat_map(|(attr, paths)| {
+                let cfg = &cfg;
+                paths.into_iter().map(move |path| {
+                    let path = signature::of_path(&path);
+                    Self {
+                        type_name: ident.to_string(),
+                        signature: format!("#[derive({})]", path),
+                        r#trait: Some(path),
+                        items: vec![],
+                        file: file.into(),
+                        line: attr.pound_token.span.start().line,
+                        cfg: cfg.clone(),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn find_in_items(items: &[syn::Item], file: &Path) -> Vec<Self> {
+        items
+            .iter()
+            .flat_map(|item| match item {
+                syn::Item::Impl(item) => Self::from_impl(item, file).into_iter().collect(),
+                syn::Item::Enum(item) => Self::from_derives(&item.ident, &item.attrs, file),
+                syn::Item::Struct(item) => Self::from_derives(&item.ident, &item.attrs, file),
+                syn::Item::Union(item) => Self::from_derives(&item.ident, &item.attrs, file),
+                _ => vec![],
             })
-            .flatten()
             .collect()
     }
 }
 
 /// A root crate
-#[derive(Debug)]
-#[allow(dead_code)]
+///
+/// It, and everything in it, can be serialized with serde, e.g. with the
+/// [`Json`](render::Json) renderer, and read back with [`Lib::from_json`].
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct Lib {
     name: String,
     path: Box<Path>,
     interface: Vec<PublicIdentifier>,
     modules: Vec<Module>,
+    references: Vec<Reference>,
+    impls: Vec<Impl>,
+    stats: Stats,
+    external_crates: Vec<String>,
 }
 
 impl Lib {
-    pub fn from_path(name: &str, path: &Path) -> Self {
-        assert_eq!(path.file_name(), Some(std::ffi::OsStr::new("lib.rs")));
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The path to the crate's `lib.rs`.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
 
-        let contents = std::fs::read_to_string(path)
-            .unwrap_or_else(|err| panic!("Could not read {}: {}", path.to_str().unwrap(), err));
-        let parsed_file = syn::parse_file(&contents).expect("Unable to parse file.");
+    pub fn interface(&self) -> &[PublicIdentifier] {
+        &self.interface
+    }
+
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
 
-        let children: Vec<Module> = parsed_file
-            .items
+    /// The `use` declarations and paths in the crate root.
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    /// The `impl` blocks and derives in the crate root.
+    pub fn impls(&self) -> &[Impl] {
+        &self.impls
+    }
+
+    /// The size and complexity of `lib.rs`.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// The names of the crates the library depends on, as they are written
+    /// in paths (`serde_json`, not `serde-json`). Empty unless given with
+    /// [`Lib::with_external_crates`].
+    pub fn external_crates(&self) -> &[String] {
+        &self.external_crates
+    }
+
+    /// Tell the library which crates it depends on, usually those from the
+    /// `[dependencies]` of its manifest, so that paths into them can be told
+    /// apart from other paths.
+    pub fn with_external_crates(mut self, crates: Vec<String>) -> Self {
+        self.external_crates = crates;
+        self
+    }
+
+    /// Every problem found while looking for the files of the crate's
+    /// modules.
+    pub fn diagnostics(&self) -> Vec<&Diagnostic> {
+        fn collect<'a>(module: &'a Module, diagnostics: &mut Vec<&'a Diagnostic>) {
+            diagnostics.extend(module.diagnostic());
+            module
+                .children()
+                .iter()
+                .for_each(|child| collect(child, diagnostics));
+        }
+        let mut diagnostics = vec![];
+        self.modules()
             .iter()
-            .filter_map(|item| match item {
-                syn::Item::Mod(inner) => {
-                    let module = Module::from_path(
-                        &format!("{}::{}", name, inner.ident),
-                        path.parent().unwrap(),
-                    );
-                    Some(module)
+            .for_each(|module| collect(module, &mut diagnostics));
+        diagnostics
+    }
+
+    /// The `.rs` files in the crate's source directory that no `mod`
+    /// declaration reaches, and so are never compiled. Binaries (`main.rs`
+    /// and `bin/`) are compiled on their own and are not orphans. Modules
+    /// whose file is given with a `#[path]` attribute are not followed, so
+    /// their files are reported as orphans too.
+    pub fn orphans(&self) -> Vec<std::path::PathBuf> {
+        self.orphans_in(&FileSystem)
+    }
+
+    /// [`Lib::orphans`], looking for `.rs` files in `source`.
+    pub fn orphans_in(&self, source: &dyn Source) -> Vec<std::path::PathBuf> {
+        fn collect<'a>(module: &'a Module, files: &mut Vec<&'a Path>) {
+            files.extend(module.file());
+            module
+                .children()
+                .iter()
+                .for_each(|child| collect(child, files));
+        }
+        fn walk(
+            source: &dyn Source,
+            dir: &Path,
+            files: &[&Path],
+            orphans: &mut Vec<std::path::PathBuf>,
+        ) {
+            let Ok(entries) = source.read_dir(dir) else {
+                return;
+            };
+            for path in entries {
+                if source.is_dir(&path) {
+                    walk(source, &path, files, orphans);
+                } else if path.extension() == Some(std::ffi::OsStr::new("rs"))
+                    && !files.contains(&path.as_path())
+                {
+                    orphans.push(path);
                 }
-                _ => None,
-            })
-            .collect();
+            }
+        }
+        let mut files = vec![self.path()];
+        self.modules()
+            .iter()
+            .for_each(|module| collect(module, &mut files));
 
-        let interface: Vec<PublicIdentifier> = PublicIdentifier::find_in_items(&parsed_file.items);
+        let src = self.path().parent().unwrap();
+        let mut orphans = vec![];
+        walk(source, src, &files, &mut orphans);
+        orphans.retain(|path| {
+            path.as_path() != src.join("main.rs") && !path.starts_with(src.join("bin"))
+        });
+        orphans.sort();
+        orphans
+    }
 
-        Self {
+    /// The crate as a mermaid entity diagram.
+    #[deprecated(since = "0.3.0", note = "use the `thigpen::render::Mermaid` renderer")]
+    pub fn create_mermaid(&self) -> String {
+        let mut mermaid = vec![];
+        render::Renderer::render(&render::Mermaid::default(), self, &mut mermaid)
+            .expect("writing to a Vec does not fail");
+        String::from_utf8(mermaid).expect("mermaid output is UTF-8")
+    }
+
+    /// The crate whose root is the `lib.rs` at `path`, named `name`.
+    ///
+    /// # Panics
+    ///
+    /// If a file of the crate can't be read or parsed. Use
+    /// [`Lib::from_source`] with [`FileSystem`] to get the error instead.
+    pub fn from_path(name: &str, path: &Path) -> Self {
+        Self::from_source(name, path, &FileSystem).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Read back a crate analyzed earlier and written as JSON by the
+    /// [`Json`](render::Json) renderer. Paths are as they were when it was
+    /// analyzed.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// [`Lib::from_path`], reading the crate's files from `source`, or the
+    /// first file that could not be read or parsed.
+    pub fn from_source(name: &str, path: &Path, source: &dyn Source) -> Result<Self, ReadError> {
+        assert_eq!(path.file_name(), Some(std::ffi::OsStr::new("lib.rs")));
+
+        let (contents, parsed_file) = parse(source, path)?;
+
+        let children = Module::find_in_items(
+            source,
+            name,
+            &parsed_file.items,
+            path.parent().unwrap(),
+            path,
+        )?;
+
+        let interface: Vec<PublicIdentifier> =
+            PublicIdentifier::find_in_items(&parsed_file.items, path);
+        let references = Reference::find_in_file(name, &parsed_file, path);
+        let impls = Impl::find_in_items(&parsed_file.items, path);
+        let stats = Stats::from_file(&contents, &parsed_file);
+
+        Ok(Self {
             name: name.into(),
             path: path.into(),
             interface,
             modules: children,
-        }
+            references,
+            impls,
+            stats,
+            external_crates: vec![],
+        })
     }
+}
 
-    #[allow(dead_code)]
-    fn create_dot(&self) -> String {
-        let mut dot = String::new();
-        writeln!(&mut dot, "graph graphname {{").unwrap();
-        for module in self.modules.iter() {
-            writeln!(&mut dot, "\"{}\" -- \"{}\"", self.name, module.usepath).unwrap();
-            module.write_dot(&mut dot);
-        }
-        writeln!(&mut dot, "}}").unwrap();
+/// The reason a crate could not be read.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(std::path::PathBuf, std::io::Error),
+    Parse(std::path::PathBuf, syn::Error),
+}
 
-        dot
+impl std::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Io(path, err) => write!(f, "Could not read {}: {}", path.display(), err),
+            ReadError::Parse(path, err) => {
+                let start = err.span().start();
+                write!(
+                    f,
+                    "Could not parse {}:{}:{}: {}",
+                    path.display(),
+                    start.line,
+                    start.column + 1,
+                    err
+                )
+            }
+        }
     }
+}
 
-    pub fn create_mermaid(&self) -> String {
-        let mut mermaid = String::new();
-        write!(
-            &mut mermaid,
-            r#"---
-title: {} entity diagram
----
-erDiagram
-"#,
-            self.name
-        )
-        .unwrap();
-        self.modules.iter().for_each(|module| {
-            writeln!(
-                &mut mermaid,
-                "  \"{}\" ||--|{{ \"{}\" : \"\"",
-                self.name, module.usepath
-            )
-            .unwrap();
-            module.write_mermaid(&mut mermaid);
-        });
-        writeln!(&mut mermaid, "  \"{}\" {{", self.name).unwrap();
-        self.interface.iter().for_each(|item| {
-            writeln!(&mut mermaid, "    {} {}", item.r#type, item.name).unwrap();
-        });
-        writeln!(&mut mermaid, "  }}").unwrap();
+impl std::error::Error for ReadError {}
 
-        mermaid
-    }
+/// The contents of the file at `path` in `source`, and the file parsed.
+fn parse(source: &dyn Source, path: &Path) -> Result<(String, syn::File), ReadError> {
+    log::debug!("Reading/parsing {:?}", path);
+    let contents = source
+        .read(path)
+        .map_err(|err| ReadError::Io(path.into(), err))?;
+    let parsed_file =
+        syn::parse_file(&contents).map_err(|err| ReadError::Parse(path.into(), err))?;
+    Ok((contents, parsed_file))
 }
 
-#[derive(Debug)]
-#[allow(dead_code)]
-struct Module {
+/// A module of a crate, and the modules declared inside it.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Module {
     name: String,
     usepath: String,
     path: Option<Box<Path>>,
+    file: Option<Box<Path>>,
+    diagnostic: Option<Diagnostic>,
     interface: Vec<PublicIdentifier>,
     children: Vec<Module>,
-    dependencies: Vec<Module>,
+    references: Vec<Reference>,
+    impls: Vec<Impl>,
+    stats: Stats,
+    cfg: Vec<Cfg>,
+    cfg_attr: Vec<Cfg>,
 }
 
 impl Module {
-    fn from_path(usepath: &str, path: &Path) -> Self {
-        assert_ne!(path.file_name(), Some(std::ffi::OsStr::new("lib.rs")));
-        assert_ne!(path.extension(), Some(std::ffi::OsStr::new("rs")));
-        assert!(path.exists(), "Path at {:?} does not exist", path);
-        assert!(path.is_dir(), "Path at {:?} is not a directory", path);
-
-        let name = usepath.split("::").last().unwrap();
-
-        let paths: Vec<std::path::PathBuf> = vec![
-            {
-                let mut path = path.to_path_buf();
-                path.push(name);
-                path.push("mod");
-                path.set_extension("rs");
-                path
-            },
-            {
-                let mut path = path.to_path_buf();
-                path.push(name);
-                path.set_extension("rs");
-                path
-            },
-        ]
-        .into_iter()
-        .filter(|path| path.exists())
-        .collect();
-        if paths.is_empty() {
-            // Either the module lives inside the declaring file, or the module
-            // doesn't exist on disk. Either way, drop a "dummy" module here.
-            return Self {
-                name: name.into(),
-                usepath: usepath.into(),
-                path: None,
-                interface: vec![],
-                children: vec![],
-                dependencies: vec![],
-            };
-        }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 
-        let (children, interface): (Vec<Module>, Vec<PublicIdentifier>) = paths
-            .iter()
-            .map(|modpath| {
-                log::debug!("Reading/parsing {:?}", path);
-
-                let contents = std::fs::read_to_string(modpath).unwrap_or_else(|err| {
-                    panic!("Could not read {}: {}", modpath.to_str().unwrap(), err)
-                });
-                let parsed_file = syn::parse_file(&contents).expect("Unable to parse file.");
-                let modules: Vec<Module> = parsed_file
-                    .items
-                    .iter()
-                    .filter_map(|item| match item {
-                        syn::Item::Mod(inner) => {
-                            let mut path = path.to_path_buf();
-                            path.push(name);
-                            if path.exists() {
-                                Some(Module::from_path(
-                                    &format!("{}::{}", usepath, inner.ident),
-                                    &path,
-                                ))
-                            } else {
-                                None
-                            }
-                        }
-                        syn::Item::Use(_inner) => None,
-                        _ => None,
-                    })
-                    .collect();
+    /// The full path of the module, e.g. `thigpen::render`.
+    pub fn usepath(&self) -> &str {
+        &self.usepath
+    }
 
-                let interface: Vec<PublicIdentifier> =
-                    PublicIdentifier::find_in_items(&parsed_file.items);
+    /// The directory the module's file was found in, or `None` if it is an
+    /// inline module or its file was not found.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
 
-                (modules, interface)
-            })
-            .fold((vec![], vec![]), |mut acc, x| {
-                acc.0.extend(x.0);
-                acc.1.extend(x.1);
-                acc
-            });
+    /// The file the module was read from, which for an inline module is the
+    /// file it is declared in, or `None` if its file was not found.
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
 
-        Self {
-            name: name.into(),
-            usepath: usepath.into(),
-            path: Some(path.into()),
-            interface,
-            children,
-            dependencies: vec![],
-        }
+    /// What was wrong with finding the module's file, if anything.
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        self.diagnostic.as_ref()
     }
 
-    fn write_dot(&self, mut wrt: &mut dyn std::fmt::Write) {
-        for module in self.children.iter() {
-            writeln!(&mut wrt, "\"{}\" -- \"{}\"", self.usepath, module.usepath).unwrap();
-            module.write_dot(wrt);
-        }
+    pub fn interface(&self) -> &[PublicIdentifier] {
+        &self.interface
     }
 
-    fn write_mermaid(&self, mut wrt: &mut dyn std::fmt::Write) {
-        self.children.iter().for_each(|module| {
-            writeln!(
-                &mut wrt,
-                "  \"{}\" ||--|{{ \"{}\" : \"\"",
-                self.usepath, module.usepath
-            )
-            .unwrap();
-            module.write_mermaid(&mut wrt);
-        });
+    pub fn children(&self) -> &[Module] {
+        &self.children
+    }
 
-        writeln!(&mut wrt, "  \"{}\" {{", self.usepath).unwrap();
-        self.interface.iter().for_each(|item| {
-            writeln!(&mut wrt, "    {} {}", item.r#type, item.name).unwrap();
-        });
-        writeln!(&mut wrt, "  }}").unwrap();
+    /// The `use` declarations and paths in the module.
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    /// The `impl` blocks and derives in the module.
+    pub fn impls(&self) -> &[Impl] {
+        &self.impls
+    }
+
+    /// The size and complexity of the module's own file.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// The predicates of the `#[cfg]` attributes on the module's `mod`
+    /// declaration, which must all hold for it to exist.
+    pub fn cfg(&self) -> &[Cfg] {
+        &self.cfg
+    }
+
+    /// The predicates of the `#[cfg_attr]` attributes on the module's `mod`
+    /// declaration.
+    pub fn cfg_attr(&self) -> &[Cfg] {
+        &self.cfg_attr
+    }
+
+    /// Read back a module, and the modules beneath it, serialized as JSON,
+    /// as in the output of the [`Json`](render::Json) renderer.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Read the module declared by `item` in the file at `declared_in`,
+    /// as a child of the module at `parent`. Its file, if it has one, is in
+    /// `dir` of `source`.
+    fn from_item(
+        source: &dyn Source,
+        parent: &str,
+        item: &syn::ItemMod,
+        dir: &Path,
+        declared_in: &Path,
+    ) -> Result<Self, ReadError> {
+        let name = item.ident.to_string();
+        let usepath = format!("{}::{}", parent, name);
+        // The file of a raw identifier like `r#match` is `match.rs`.
+        let filename = name.strip_prefix("r#").unwrap_or(&name);
+        let (cfg, cfg_attr) = cfg::conditions(&item.attrs);
+
+        if let Some((_, items)) = &item.content {
+            let file = syn::File {
+                shebang: None,
+                attrs: vec![],
+                items: items.clone(),
+            };
+            return Ok(Self {
+                usepath: usepath.clone(),
+                path: None,
+                file: Some(declared_in.into()),
+                diagnostic: None,
+                interface: PublicIdentifier::find_in_items(items, declared_in),
+                children: Module::find_in_items(
+                    source,
+                    &usepath,
+                    items,
+                    &dir.join(filename),
+                    declared_in,
+                )?,
+                references: Reference::find_in_file(&usepath, &file, declared_in),
+                impls: Impl::find_in_items(items, declared_in),
+                // Its lines count towards the file it is declared in.
+                stats: Stats::default(),
+                name,
+                cfg,
+                cfg_attr,
+            });
+        }
+
+        let candidates = [
+            dir.join(filename).join("mod.rs"),
+            dir.join(format!("{}.rs", filename)),
+        ];
+        let found: Vec<&std::path::PathBuf> = candidates
+            .iter()
+            .filter(|candidate| source.is_file(candidate))
+            .collect();
+        let diagnostic = |kind| {
+            Some(Diagnostic::new(
+                kind,
+                &usepath,
+                declared_in,
+                item.ident.span().start().line,
+                &candidates,
+            ))
+        };
+        let (modpath, diagnostic) = match found[..] {
+            // A `#[path]` attribute points somewhere else, which isn't
+            // followed (yet).
+            [] if item.attrs.iter().any(|attr| attr.path().is_ident("path")) => (None, None),
+            [] => (None, diagnostic(DiagnosticKind::MissingFile)),
+            [modpath] => (Some(modpath), None),
+            // rustc refuses this (E0761). Read `foo.rs` only, rather than
+            // mixing both files into one module.
+            [_, modpath] => (Some(modpath), diagnostic(DiagnosticKind::AmbiguousFile)),
+            _ => unreachable!(),
+        };
+        let Some(modpath) = modpath else {
+            return Ok(Self {
+                name,
+                usepath,
+                path: None,
+                file: None,
+                diagnostic,
+                interface: vec![],
+                children: vec![],
+                references: vec![],
+                impls: vec![],
+                stats: Stats::default(),
+                cfg,
+                cfg_attr,
+            });
+        };
+
+        let (contents, parsed_file) = parse(source, modpath)?;
+
+        Ok(Self {
+            children: Module::find_in_items(
+                source,
+                &usepath,
+                &parsed_file.items,
+                &dir.join(filename),
+                modpath,
+            )?,
+            interface: PublicIdentifier::find_in_items(&parsed_file.items, modpath),
+            references: Reference::find_in_file(&usepath, &parsed_file, modpath),
+            impls: Impl::find_in_items(&parsed_file.items, modpath),
+            stats: Stats::from_file(&contents, &parsed_file),
+            name,
+            usepath,
+            path: Some(dir.into()),
+            file: Some(modpath.as_path().into()),
+            diagnostic,
+            cfg,
+            cfg_attr,
+        })
+    }
+
+    /// Read the modules declared in `items`, which are in the file at
+    /// `declared_in` and belong to the module at `parent`.
+    fn find_in_items(
+        source: &dyn Source,
+        parent: &str,
+        items: &[syn::Item],
+        dir: &Path,
+        declared_in: &Path,
+    ) -> Result<Vec<Self>, ReadError> {
+        items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Mod(item) => {
+                    Some(Module::from_item(source, parent, item, dir, declared_in))
+                }
+                _ => None,
+            })
+            .collect()
     }
 }
@@ -0,0 +1,121 @@
+//! Coupling metrics for each module, after Robert C. Martin's "Agile Software
+//! Development: Principles, Patterns, and Practices".
+//!
+//! Modules that are both concrete and depended upon by many others (near
+//! `(0, 0)`) are painful to change; modules that are abstract but depended
+//! upon by no one (near `(1, 1)`) are useless. The distance from the main
+//! sequence, the line between `(0, 1)` and `(1, 0)`, measures how far a
+//! module is from a good balance.
+use std::collections::HashSet;
+
+use crate::graph::ModuleGraph;
+use crate::{Lib, Module, PublicIdentifier, PublicIdentifierType, Visibility};
+
+/// The coupling metrics of a single module.
+#[derive(Debug, serde::Serialize)]
+pub struct ModuleMetrics<'a> {
+    module: &'a str,
+    afferent: usize,
+    efferent: usize,
+    instability: f64,
+    abstractness: f64,
+    distance: f64,
+}
+
+impl<'a> ModuleMetrics<'a> {
+    /// The metrics of every module in `lib`, starting with the crate root.
+    pub fn of(lib: &'a Lib) -> Vec<Self> {
+        fn collect<'a>(module: &'a Module, modules: &mut Vec<(&'a str, &'a [PublicIdentifier])>) {
+            modules.push((module.usepath(), module.interface()));
+            module
+                .children()
+                .iter()
+                .for_each(|child| collect(child, modules));
+        }
+        let mut modules = vec![(lib.name(), lib.interface())];
+        lib.modules()
+            .iter()
+            .for_each(|module| collect(module, &mut modules));
+
+        let graph = ModuleGraph::new(lib);
+        let edges: HashSet<(&str, &str)> = graph
+            .dependencies()
+            .iter()
+            .map(|dependency| (dependency.from(), dependency.to()))
+            .collect();
+
+        modules
+            .into_iter()
+            .map(|(module, interface)| {
+                let afferent = edges.iter().filter(|(_, to)| *to == module).count();
+                let efferent = edges.iter().filter(|(from, _)| *from == module).count();
+                // A module nothing depends on, and that depends on nothing,
+                // is free to change.
+                let instability = match afferent + efferent {
+                    0 => 0.0,
+                    total => efferent as f64 / total as f64,
+                };
+                let abstractness = abstractness(interface);
+                Self {
+                    module,
+                    afferent,
+                    efferent,
+                    instability,
+                    abstractness,
+                    distance: (abstractness + instability - 1.0).abs(),
+                }
+            })
+            .collect()
+    }
+
+    /// The `usepath` of the module.
+    pub fn module(&self) -> &'a str {
+        self.module
+    }
+
+    /// Afferent coupling (Ca): the number of modules depending on this one.
+    pub fn afferent(&self) -> usize {
+        self.afferent
+    }
+
+    /// Efferent coupling (Ce): the number of modules this one depends on.
+    pub fn efferent(&self) -> usize {
+        self.efferent
+    }
+
+    /// Ce / (Ca + Ce), from 0 (stable) to 1 (unstable).
+    pub fn instability(&self) -> f64 {
+        self.instability
+    }
+
+    /// The share of traits among the traits and concrete types (structs,
+    /// enums and unions) declared `pub` in the module, from 0 (concrete) to 1
+    /// (abstract).
+    pub fn abstractness(&self) -> f64 {
+        self.abstractness
+    }
+
+    /// |A + I - 1|, from 0 (on the main sequence) to 1 (as far off as can be).
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+}
+
+fn abstractness(interface: &[PublicIdentifier]) -> f64 {
+    let (traits, types) = interface
+        .iter()
+        .filter(|item| *item.visibility() == Visibility::Public)
+        .fold((0, 0), |(traits, types), item| match item.kind() {
+            PublicIdentifierType::Trait | PublicIdentifierType::TraitAlias => {
+                (traits + 1, types + 1)
+            }
+            PublicIdentifierType::Struct
+            | PublicIdentifierType::Enum
+            | PublicIdentifierType::Union => (traits, types + 1),
+            _ => (traits, types),
+        });
+    match types {
+        0 => 0.0,
+        types => traits as f64 / types as f64,
+    }
+}
@@ -0,0 +1,108 @@
+//! Patterns matching module paths, like `crate::domain::**`.
+//!
+//! Patterns are matched segment by segment. `*` matches any one segment, `**`
+//! matches any number of segments (including none), and a `*` inside a
+//! segment, like `test*`, matches any run of characters. A leading `crate`
+//! matches the name of the crate being analyzed.
+
+/// A pattern matching module paths.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct ModulePattern {
+    pattern: String,
+    segments: Vec<String>,
+}
+
+/// The reason a pattern could not be parsed.
+#[derive(Debug)]
+pub struct PatternError {
+    pattern: String,
+}
+
+impl std::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid module pattern {:?}: segments must not be empty",
+            self.pattern
+        )
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl ModulePattern {
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let segments: Vec<String> = pattern.split("::").map(String::from).collect();
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return Err(PatternError {
+                pattern: pattern.into(),
+            });
+        }
+        Ok(Self {
+            pattern: pattern.into(),
+            segments,
+        })
+    }
+
+    /// Whether the module at `usepath` in the crate `crate_name` matches.
+    pub fn matches(&self, usepath: &str, crate_name: &str) -> bool {
+        let path: Vec<&str> = usepath.split("::").collect();
+        let segments: Vec<&str> = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                if index == 0 && segment == "crate" {
+                    crate_name
+                } else {
+                    segment.as_str()
+                }
+            })
+            .collect();
+        matches_segments(&segments, &path)
+    }
+}
+
+impl TryFrom<String> for ModulePattern {
+    type Error = PatternError;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        Self::new(&pattern)
+    }
+}
+
+impl std::fmt::Display for ModulePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
+fn matches_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.first(), path.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            matches_segments(&pattern[1..], path)
+                || (!path.is_empty() && matches_segments(pattern, &path[1..]))
+        }
+        (Some(segment), Some(name)) => {
+            matches_segment(segment, name) && matches_segments(&pattern[1..], &path[1..])
+        }
+        _ => false,
+    }
+}
+
+/// Match a single segment, where `*` stands for any run of characters.
+fn matches_segment(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=name.len())
+                .filter(|index| name.is_char_boundary(*index))
+                .any(|index| matches_segment(rest, &name[index..]))
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use syn::visit::Visit;
+
+use crate::source::{FileSystem, Source};
+
+/// How a module refers to a path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReferenceKind {
+    /// A `use` declaration.
+    Use,
+    /// A path in an expression, type, pattern or macro invocation.
+    Path,
+}
+
+impl std::fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReferenceKind::Use => write!(f, "use"),
+            ReferenceKind::Path => write!(f, "path"),
+        }
+    }
+}
+
+/// A path referred to from a module, e.g. `use crate::net::Client` or
+/// `super::connect()`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Reference {
+    path: Vec<String>,
+    written: String,
+    kind: ReferenceKind,
+    file: Box<Path>,
+    line: usize,
+}
+
+impl Reference {
+    /// The referenced path, resolved as far as the referring module allows.
+    /// Paths into the crate start with the crate's name, regardless of
+    /// whether they were written relative to `crate`, `self`, `super`, a
+    /// child module, or a name brought in by a `use`. Other paths (other
+    /// crates, generic parameters, the prelude) are left as written.
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// The path as it was written in the source.
+    pub fn written(&self) -> &str {
+        &self.written
+    }
+
+    pub fn kind(&self) -> ReferenceKind {
+        self.kind
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// The line the reference is on, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Find every `use` declaration and path in the `.rs` file at `path`,
+    /// or in the `.rs` files beneath it if it is a directory, e.g. a crate's
+    /// `tests/` or `build.rs`. These are separate crates, so paths into the
+    /// library start with its name, as they do after resolution. Files that
+    /// can't be read or parsed are skipped.
+    pub fn find_in_path(path: &Path) -> Vec<Self> {
+        Self::find_in_source(path, &FileSystem)
+    }
+
+    /// [`Reference::find_in_path`], reading the files from `source`.
+    pub fn find_in_source(path: &Path, source: &dyn Source) -> Vec<Self> {
+        if source.is_dir(path) {
+            let Ok(mut paths) = source.read_dir(path) else {
+                return vec![];
+            };
+            paths.sort();
+            return paths
+                .iter()
+                .flat_map(|path| Self::find_in_source(path, source))
+                .collect();
+        }
+        if !source.is_file(path) || path.extension() != Some(std::ffi::OsStr::new("rs")) {
+            return vec![];
+        }
+
+        log::debug!("Reading/parsing {:?}", path);
+        let parsed_file = match source
+            .read(path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| syn::parse_file(&contents).map_err(|err| err.to_string()))
+        {
+            Ok(parsed_file) => parsed_file,
+            Err(err) => {
+                log::warn!("Skipping {:?}: {}", path, err);
+                return vec![];
+            }
+        };
+        let usepath = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().replace('-', "_"))
+            .unwrap_or_default();
+        Self::find_in_file(&usepath, &parsed_file, path)
+    }
+
+    /// Find every `use` declaration and path in `file`, which holds the
+    /// module at `usepath`. Inline modules (`mod foo { ... }`) are skipped.
+    pub(crate) fn find_in_file(usepath: &str, file: &syn::File, path: &Path) -> Vec<Self> {
+        let mut scope = Scope {
+            usepath: usepath.split("::").map(String::from).collect(),
+            local: file
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    syn::Item::Const(item) => Some(item.ident.to_string()),
+                    syn::Item::Enum(item) => Some(item.ident.to_string()),
+                    syn::Item::Fn(item) => Some(item.sig.ident.to_string()),
+                    syn::Item::Mod(item) => Some(item.ident.to_string()),
+                    syn::Item::Static(item) => Some(item.ident.to_string()),
+                    syn::Item::Struct(item) => Some(item.ident.to_string()),
+                    syn::Item::Trait(item) => Some(item.ident.to_string()),
+                    syn::Item::TraitAlias(item) => Some(item.ident.to_string()),
+                    syn::Item::Type(item) => Some(item.ident.to_string()),
+                    syn::Item::Union(item) => Some(item.ident.to_string()),
+                    _ => None,
+                })
+                .collect(),
+            aliases: HashMap::new(),
+        };
+
+        // Names brought in by `use` anywhere in the module can be used by
+        // paths anywhere in it, so collect all of those first.
+        let mut uses = UseCollector {
+            scope: &scope,
+            file: path,
+            uses: vec![],
+        };
+        uses.visit_file(file);
+        let uses = uses.uses;
+        for (alias, reference) in uses.iter() {
+            if let Some(alias) = alias {
+                scope.aliases.insert(alias.clone(), reference.path.clone());
+            }
+        }
+
+        let mut paths = PathCollector {
+            scope: &scope,
+            file: path,
+            references: uses.into_iter().map(|(_, reference)| reference).collect(),
+        };
+        paths.visit_file(file);
+        paths.references
+    }
+}
+
+/// What a module's paths can be resolved against.
+struct Scope {
+    usepath: Vec<String>,
+    /// Names of the items (including modules) declared in the module.
+    local: Vec<String>,
+    /// Names brought into the module by `use`, and the paths they stand for.
+    aliases: HashMap<String, Vec<String>>,
+}
+
+impl Scope {
+    fn resolve(&self, segments: &[String], leading_colon: bool) -> Vec<String> {
+        let crate_name = &self.usepath[0];
+        let Some(first) = segments.first() else {
+            return vec![];
+        };
+        if leading_colon {
+            return segments.to_vec();
+        }
+        match first.as_str() {
+            "crate" => [std::slice::from_ref(crate_name), &segments[1..]].concat(),
+            "self" => [&self.usepath[..], &segments[1..]].concat(),
+            "super" => {
+                let supers = segments.iter().take_while(|s| *s == "super").count();
+                let depth = self.usepath.len().saturating_sub(supers).max(1);
+                [&self.usepath[..depth], &segments[supers..]].concat()
+            }
+            _ if first == crate_name => segments.to_vec(),
+            _ if self.local.contains(first) => [&self.usepath[..], segments].concat(),
+            _ => match self.aliases.get(first) {
+                Some(path) => [&path[..], &segments[1..]].concat(),
+                None => segments.to_vec(),
+            },
+        }
+    }
+}
+
+fn segments(path: &syn::Path) -> Vec<String> {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect()
+}
+
+/// Collects `use` declarations, along with the name each binds, if any.
+struct UseCollector<'a> {
+    scope: &'a Scope,
+    file: &'a Path,
+    uses: Vec<(Option<String>, Reference)>,
+}
+
+impl UseCollector<'_> {
+    fn add_tree(&mut self, tree: &syn::UseTree, prefix: &mut Vec<String>, leading_colon: bool) {
+        let (segments, alias, line) = match tree {
+            syn::UseTree::Path(path) => {
+                prefix.push(path.ident.to_string());
+                self.add_tree(&path.tree, prefix, leading_colon);
+                prefix.pop();
+                return;
+            }
+            syn::UseTree::Group(group) => {
+                group
+                    .items
+                    .iter()
+                    .for_each(|tree| self.add_tree(tree, prefix, leading_colon));
+                return;
+            }
+            // `use foo::{self}` binds `foo`.
+            syn::UseTree::Name(name) if name.ident == "self" => (
+                prefix.clone(),
+                prefix.last().cloned(),
+                name.ident.span().start().line,
+            ),
+            syn::UseTree::Name(name) => (
+                [&prefix[..], &[name.ident.to_string()]].concat(),
+                Some(name.ident.to_string()),
+                name.ident.span().start().line,
+            ),
+            syn::UseTree::Rename(rename) if rename.ident == "self" => (
+                prefix.clone(),
+                Some(rename.rename.to_string()),
+                rename.rename.span().start().line,
+            ),
+            syn::UseTree::Rename(rename) => (
+                [&prefix[..], &[rename.ident.to_string()]].concat(),
+                Some(rename.rename.to_string()),
+                rename.rename.span().start().line,
+            ),
+            syn::UseTree::Glob(glob) => (
+                [&prefix[..], &["*".to_string()]].concat(),
+                None,
+                glob.star_token.span.start().line,
+            ),
+        };
+        let written = format!(
+            "{}{}",
+            if leading_colon { "::" } else { "" },
+            segments.join("::")
+        );
+        // A glob resolves like the path it imports from, followed by `*`.
+        let resolved = match segments.last().map(String::as_str) {
+            Some("*") => {
+                let mut resolved = self
+                    .scope
+                    .resolve(&segments[..segments.len() - 1], leading_colon);
+                resolved.push("*".into());
+                resolved
+            }
+            _ => self.scope.resolve(&segments, leading_colon),
+        };
+        self.uses.push((
+            alias.filter(|alias| alias != "_"),
+            Reference {
+                path: resolved,
+                written,
+                kind: ReferenceKind::Use,
+                file: self.file.into(),
+                line,
+            },
+        ));
+    }
+}
+
+impl<'ast> Visit<'ast> for UseCollector<'_> {
+    fn visit_item_use(&mut self, item: &'ast syn::ItemUse) {
+        self.add_tree(&item.tree, &mut vec![], item.leading_colon.is_some());
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        // Inline modules have their own scope.
+        if item.content.is_none() {
+            syn::visit::visit_item_mod(self, item);
+        }
+    }
+}
+
+/// Collects paths of more than one segment, e.g. `net::Client` or
+/// `crate::connect`. Single segment paths only name something local or
+/// something already brought in by a `use`.
+struct PathCollector<'a> {
+    scope: &'a Scope,
+    file: &'a Path,
+    references: Vec<Reference>,
+}
+
+impl<'ast> Visit<'ast> for PathCollector<'_> {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if path.segments.len() > 1 || path.leading_colon.is_some() {
+            let segments = segments(path);
+            self.references.push(Reference {
+                path: self.scope.resolve(&segments, path.leading_colon.is_some()),
+                written: format!(
+                    "{}{}",
+                    if path.leading_colon.is_some() {
+                        "::"
+                    } else {
+                        ""
+                    },
+                    segments.join("::")
+                ),
+                kind: ReferenceKind::Path,
+                file: self.file.into(),
+                line: path.segments[0].ident.span().start().line,
+            });
+        }
+        syn::visit::visit_path(self, path);
+    }
+
+    fn visit_item_use(&mut self, _item: &'ast syn::ItemUse) {
+        // Already collected by UseCollector.
+    }
+
+    fn visit_attribute(&mut self, attr: &'ast syn::Attribute) {
+        // The paths in `#[derive(...)]` are only tokens to syn.
+        if attr.path().is_ident("derive") {
+            if let Ok(paths) = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+            ) {
+                paths.iter().for_each(|path| self.visit_path(path));
+            }
+        }
+        syn::visit::visit_attribute(self, attr);
+    }
+
+    fn visit_vis_restricted(&mut self, _vis: &'ast syn::VisRestricted) {
+        // `pub(in crate::foo)` limits visibility; it doesn't refer to `foo`.
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        if item.content.is_none() {
+            syn::visit::visit_item_mod(self, item);
+        }
+    }
+}
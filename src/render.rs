@@ -0,0 +1,108 @@
+//! Output formats for an analyzed crate.
+//!
+//! Every format implements [`Renderer`]. The built-in renderers are
+//! registered by name in [`Renderers::new`]; library users can register
+//! their own alongside them.
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::graph::ModuleGraph;
+use crate::Lib;
+
+mod csv;
+mod dot;
+pub mod escape;
+mod json;
+mod mermaid;
+mod tree;
+
+pub use csv::Csv;
+pub use dot::Dot;
+pub use json::Json;
+pub use mermaid::Mermaid;
+pub use tree::Tree;
+
+/// A format that an analyzed crate can be written out as.
+pub trait Renderer {
+    /// Write `lib` to `out`.
+    fn render(&self, lib: &Lib, out: &mut dyn std::io::Write) -> std::io::Result<()>;
+
+    /// Write `lib` to the file at `path`. Renderers that produce more than
+    /// one file override this.
+    fn render_to_path(&self, lib: &Lib, path: &Path) -> std::io::Result<()> {
+        let mut file = std::fs::File::create(path)?;
+        self.render(lib, &mut file)
+    }
+}
+
+/// A set of renderers, looked up by name.
+pub struct Renderers {
+    renderers: Vec<(String, Box<dyn Renderer>)>,
+}
+
+impl Renderers {
+    /// The built-in renderers: `mermaid`, `dot`, `tree`, `csv`, `tsv` and
+    /// `json`.
+    pub fn new() -> Self {
+        let mut renderers = Self::empty();
+        renderers.register("mermaid", Box::new(Mermaid::default()));
+        renderers.register("dot", Box::new(Dot::default()));
+        renderers.register("tree", Box::new(Tree::default()));
+        renderers.register("csv", Box::new(Csv::new(',')));
+        renderers.register("tsv", Box::new(Csv::new('\t')));
+        renderers.register("json", Box::new(Json));
+        renderers
+    }
+
+    /// A set of renderers without the built-in ones.
+    pub fn empty() -> Self {
+        Self { renderers: vec![] }
+    }
+
+    /// Register `renderer` as `name`, replacing any renderer already
+    /// registered under that name.
+    pub fn register(&mut self, name: &str, renderer: Box<dyn Renderer>) {
+        match self
+            .renderers
+            .iter_mut()
+            .find(|(existing, _)| existing == name)
+        {
+            Some(entry) => entry.1 = renderer,
+            None => self.renderers.push((name.into(), renderer)),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Renderer> {
+        self.renderers
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, renderer)| renderer.as_ref())
+    }
+
+    /// The names of all registered renderers, in registration order.
+    pub fn names(&self) -> Vec<String> {
+        self.renderers
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+impl Default for Renderers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The [external crates](Lib::external_crates) each module uses, by the
+/// module's `usepath`.
+fn external_crates(lib: &Lib) -> BTreeMap<&str, BTreeSet<&str>> {
+    let mut crates: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for dependency in ModuleGraph::new(lib).external_dependencies() {
+        crates
+            .entry(dependency.from())
+            .or_default()
+            .insert(dependency.to());
+    }
+    crates
+}
@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use crate::render::escape::csv_field;
+use crate::render::{external_crates, Renderer};
+use crate::{Lib, Module, PublicIdentifier};
+
+/// Two delimiter separated tables: one row per public identifier, and one
+/// row per edge between modules (of kind `mod`) or from a module to an
+/// [external crate](Lib::external_crates) it uses (of kind `extern`).
+pub struct Csv {
+    delimiter: char,
+}
+
+impl Csv {
+    /// Fields are quoted or escaped as described in
+    /// [`csv_field`](crate::render::escape::csv_field).
+    pub fn new(delimiter: char) -> Self {
+        Self { delimiter }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self.delimiter {
+            ',' => "csv",
+            '\t' => "tsv",
+            _ => "txt",
+        }
+    }
+
+    fn write_row(&self, out: &mut dyn std::io::Write, fields: &[&str]) -> std::io::Result<()> {
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|field| csv_field(field, self.delimiter))
+            .collect();
+        writeln!(out, "{}", fields.join(&self.delimiter.to_string()))
+    }
+
+    fn write_items(
+        &self,
+        out: &mut dyn std::io::Write,
+        lib: &Lib,
+        usepath: &str,
+        interface: &[PublicIdentifier],
+    ) -> std::io::Result<()> {
+        // Files are relative to the crate's directory, above the one holding
+        // its root file, so the table doesn't depend on where the crate is.
+        let dir = lib.path().parent().and_then(Path::parent);
+        for item in interface {
+            let file = dir
+                .and_then(|dir| item.file().strip_prefix(dir).ok())
+                .unwrap_or(item.file());
+            self.write_row(
+                out,
+                &[
+                    lib.name(),
+                    usepath,
+                    &item.kind().to_string(),
+                    item.name(),
+                    &item.visibility().to_string(),
+                    &file.to_string_lossy(),
+                    &item.line().to_string(),
+                ],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn write_item_table(&self, out: &mut dyn std::io::Write, lib: &Lib) -> std::io::Result<()> {
+        self.write_row(
+            out,
+            &[
+                "crate",
+                "module",
+                "kind",
+                "name",
+                "visibility",
+                "file",
+                "line",
+            ],
+        )?;
+        self.write_items(out, lib, lib.name(), lib.interface())?;
+        for module in lib.modules() {
+            self.write_module_items(out, lib, module)?;
+        }
+        Ok(())
+    }
+
+    fn write_module_items(
+        &self,
+        out: &mut dyn std::io::Write,
+        lib: &Lib,
+        module: &Module,
+    ) -> std::io::Result<()> {
+        self.write_items(out, lib, module.usepath(), module.interface())?;
+        for child in module.children() {
+            self.write_module_items(out, lib, child)?;
+        }
+        Ok(())
+    }
+
+    fn write_edge_table(&self, out: &mut dyn std::io::Write, lib: &Lib) -> std::io::Result<()> {
+        self.write_row(out, &["from", "to", "kind"])?;
+        for module in lib.modules() {
+            self.write_module_edges(out, lib.name(), module)?;
+        }
+        for (module, crates) in external_crates(lib) {
+            for name in crates {
+                self.write_row(out, &[module, name, "extern"])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_module_edges(
+        &self,
+        out: &mut dyn std::io::Write,
+        parent: &str,
+        module: &Module,
+    ) -> std::io::Result<()> {
+        self.write_row(out, &[parent, module.usepath(), "mod"])?;
+        for child in module.children() {
+            self.write_module_edges(out, module.usepath(), child)?;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for Csv {
+    /// Write the items table, a blank line, then the edges table.
+    fn render(&self, lib: &Lib, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.write_item_table(out, lib)?;
+        writeln!(out)?;
+        self.write_edge_table(out, lib)
+    }
+
+    /// Use `path` as a prefix, writing the tables to `<path>.items.csv` and
+    /// `<path>.edges.csv`.
+    fn render_to_path(&self, lib: &Lib, path: &Path) -> std::io::Result<()> {
+        let mut items = path.as_os_str().to_owned();
+        items.push(format!(".items.{}", self.extension()));
+        self.write_item_table(&mut std::fs::File::create(items)?, lib)?;
+
+        let mut edges = path.as_os_str().to_owned();
+        edges.push(format!(".edges.{}", self.extension()));
+        self.write_edge_table(&mut std::fs::File::create(edges)?, lib)
+    }
+}
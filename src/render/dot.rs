@@ -0,0 +1,93 @@
+use std::collections::BTreeSet;
+
+use crate::render::escape::dot_id;
+use crate::render::{external_crates, Renderer};
+use crate::stats::Stats;
+use crate::{Lib, Module};
+
+/// A graphviz graph of the module hierarchy. The
+/// [external crates](Lib::external_crates) used by each module are dashed
+/// boxes.
+#[derive(Default)]
+pub struct Dot {
+    scale: bool,
+}
+
+impl Dot {
+    /// A graph whose nodes are sized by the lines of code in each module.
+    pub fn scaled() -> Self {
+        Self { scale: true }
+    }
+}
+
+impl Renderer for Dot {
+    fn render(&self, lib: &Lib, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        writeln!(out, "graph graphname {{")?;
+        if self.scale {
+            let mut sizes = vec![(lib.name(), lib.stats())];
+            lib.modules()
+                .iter()
+                .for_each(|module| collect_stats(module, &mut sizes));
+            let largest = sizes
+                .iter()
+                .map(|(_, stats)| stats.lines().code)
+                .max()
+                .unwrap_or_default()
+                .max(1);
+            for (usepath, stats) in sizes {
+                // Scale the area, not the width, with the lines of code.
+                let size = (stats.lines().code as f64 / largest as f64).sqrt();
+                writeln!(
+                    out,
+                    "{} [fontsize={:.0}]",
+                    dot_id(usepath),
+                    MIN_FONTSIZE + (MAX_FONTSIZE - MIN_FONTSIZE) * size
+                )?;
+            }
+        }
+        for module in lib.modules() {
+            writeln!(
+                out,
+                "{} -- {}",
+                dot_id(lib.name()),
+                dot_id(module.usepath())
+            )?;
+            write_module(out, module)?;
+        }
+        let externals = external_crates(lib);
+        let names: BTreeSet<&str> = externals.values().flatten().copied().collect();
+        for name in names {
+            writeln!(out, "{} [shape=box, style=dashed]", dot_id(name))?;
+        }
+        for (module, crates) in externals {
+            for name in crates {
+                writeln!(out, "{} -- {} [style=dashed]", dot_id(module), dot_id(name))?;
+            }
+        }
+        writeln!(out, "}}")
+    }
+}
+
+const MIN_FONTSIZE: f64 = 10.0;
+const MAX_FONTSIZE: f64 = 40.0;
+
+fn collect_stats<'a>(module: &'a Module, sizes: &mut Vec<(&'a str, &'a Stats)>) {
+    sizes.push((module.usepath(), module.stats()));
+    module
+        .children()
+        .iter()
+        .for_each(|child| collect_stats(child, sizes));
+}
+
+fn write_module(out: &mut dyn std::io::Write, module: &Module) -> std::io::Result<()> {
+    for child in module.children() {
+        writeln!(
+            out,
+            "{} -- {}",
+            dot_id(module.usepath()),
+            dot_id(child.usepath())
+        )?;
+        write_module(out, child)?;
+    }
+    Ok(())
+}
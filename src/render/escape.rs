@@ -0,0 +1,108 @@
+//! Escaping and quoting of names for each output format.
+//!
+//! Names reaching a renderer are not guaranteed to be plain ASCII words: raw
+//! identifiers keep their `r#` prefix, identifiers may be unicode, item kinds
+//! like `extern crate` contain spaces, and crate names handed to
+//! [`crate::Lib::from_path`] can contain anything at all.
+use std::borrow::Cow;
+
+/// A quoted mermaid string, e.g. an entity name or an attribute comment.
+/// Mermaid strings cannot contain `"` or line breaks, so quotes are written
+/// as the `#quot;` entity code and line breaks as spaces.
+pub fn mermaid_string(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('"', "#quot;").replace(['\n', '\r'], " ")
+    )
+}
+
+/// A bare mermaid word, as used for an attribute's type and name. Anything
+/// that is not an ASCII letter, digit, `_` or `-` is replaced by `_`, and
+/// words not starting with a letter or `_` are prefixed with `_`.
+pub fn mermaid_word(text: &str) -> Cow<'_, str> {
+    let is_word = text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if is_word {
+        return Cow::Borrowed(text);
+    }
+
+    let mut word: String = text
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        word.insert(0, '_');
+    }
+    Cow::Owned(word)
+}
+
+/// A file name (without extension) for a module path. `::` separators are
+/// written as `.`, and anything that is not a letter, digit, `_` or `-` as
+/// `_`.
+pub fn file_stem(text: &str) -> String {
+    text.replace("::", ".")
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// A quoted graphviz ID.
+pub fn dot_id(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+    )
+}
+
+/// A single field of a delimiter separated table. With a `,` delimiter,
+/// fields are quoted as described in RFC 4180; with any other delimiter, the
+/// delimiter and line breaks are replaced by spaces, as TSV has no quoting.
+pub fn csv_field(text: &str, delimiter: char) -> Cow<'_, str> {
+    if delimiter == ',' {
+        if text.contains([',', '"', '\n', '\r']) {
+            Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+        } else {
+            Cow::Borrowed(text)
+        }
+    } else if text.contains([delimiter, '\n', '\r']) {
+        Cow::Owned(text.replace([delimiter, '\n', '\r'], " "))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Text for a terminal, with control characters (which could move the
+/// cursor or inject escape sequences) written as Rust escapes.
+pub fn terminal_text(text: &str) -> Cow<'_, str> {
+    if !text.contains(char::is_control) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.chars()
+            .map(|c| {
+                if c.is_control() {
+                    c.escape_debug().to_string()
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect(),
+    )
+}
@@ -0,0 +1,15 @@
+use crate::render::Renderer;
+use crate::Lib;
+
+/// The whole analysis as JSON, which [`Lib::from_json`] reads back, so that
+/// it can be saved and rendered, compared or checked later without the
+/// source.
+#[derive(Default)]
+pub struct Json;
+
+impl Renderer for Json {
+    fn render(&self, lib: &Lib, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, lib)?;
+        writeln!(out)
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::io::Write;
+use std::path::Path;
+
+use crate::render::escape::{file_stem, mermaid_string, mermaid_word};
+use crate::render::{external_crates, Renderer};
+use crate::{Lib, Module, PublicIdentifier};
+
+/// A mermaid entity diagram, with one entity per module. The
+/// [external crates](Lib::external_crates) used by each module are entities
+/// too, named `extern crate <name>`.
+///
+/// Mermaid refuses diagrams past a certain size, so large crates can be split
+/// into pages: one diagram per top-level module, optionally splitting any
+/// subtree of more than a threshold of modules onto a page of its own.
+#[derive(Default)]
+pub struct Mermaid {
+    split: Option<usize>,
+}
+
+/// A page of a split diagram.
+struct Page<'a> {
+    module: &'a Module,
+    /// The `usepath` of the page this page was split off from.
+    parent: String,
+}
+
+impl Mermaid {
+    /// A diagram split into pages, written by
+    /// [`render_to_path`](Renderer::render_to_path) into a directory. Each
+    /// top-level module gets a page; with a `threshold`, so does any module
+    /// with more than `threshold` modules beneath it.
+    pub fn split(threshold: Option<usize>) -> Self {
+        Self {
+            split: Some(threshold.unwrap_or(usize::MAX)),
+        }
+    }
+
+    fn write_header(&self, out: &mut dyn std::io::Write, title: &str) -> std::io::Result<()> {
+        write!(
+            out,
+            r#"---
+title: {} entity diagram
+---
+erDiagram
+"#,
+            title.replace(['\n', '\r'], " ")
+        )
+    }
+
+    /// Write `module` and its children, except for those children that are
+    /// big enough to be split onto a page of their own, which are added to
+    /// `pages` instead.
+    fn write_module<'a>(
+        &self,
+        out: &mut dyn std::io::Write,
+        module: &'a Module,
+        externals: &BTreeMap<&str, BTreeSet<&str>>,
+        pages: &mut VecDeque<Page<'a>>,
+        page: &str,
+    ) -> std::io::Result<()> {
+        for child in module.children() {
+            writeln!(
+                out,
+                "  {} ||--|{{ {} : \"\"",
+                mermaid_string(module.usepath()),
+                mermaid_string(child.usepath())
+            )?;
+            if self
+                .split
+                .is_some_and(|threshold| descendants(child) > threshold)
+            {
+                pages.push_back(Page {
+                    module: child,
+                    parent: page.into(),
+                });
+            } else {
+                self.write_module(out, child, externals, pages, page)?;
+            }
+        }
+
+        write_externals(out, module.usepath(), externals)?;
+        write_entity(out, module.usepath(), module.interface())
+    }
+
+    /// Write an index of the pages of a split diagram, as a flowchart
+    /// linking to each page's file. `pages` holds the `usepath` of each
+    /// page, that of the page it was split off from, and its file name.
+    fn write_index(
+        &self,
+        out: &mut dyn std::io::Write,
+        lib: &Lib,
+        pages: &[(String, String, String)],
+    ) -> std::io::Result<()> {
+        write!(
+            out,
+            r#"---
+title: {} index
+---
+flowchart TD
+"#,
+            lib.name().replace(['\n', '\r'], " ")
+        )?;
+        for (index, (usepath, parent, filename)) in pages.iter().enumerate() {
+            writeln!(out, "  page{}[{}]", index, mermaid_string(usepath))?;
+            if let Some(parent) = pages.iter().position(|(usepath, _, _)| usepath == parent) {
+                if parent != index {
+                    writeln!(out, "  page{} --> page{}", parent, index)?;
+                }
+            }
+            writeln!(out, "  click page{} {}", index, mermaid_string(filename))?;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for Mermaid {
+    fn render(&self, lib: &Lib, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.write_header(out, lib.name())?;
+        // Pages can only be written by render_to_path; here, everything goes
+        // into the one diagram.
+        let unsplit = Mermaid::default();
+        let externals = external_crates(lib);
+        for module in lib.modules() {
+            writeln!(
+                out,
+                "  {} ||--|{{ {} : \"\"",
+                mermaid_string(lib.name()),
+                mermaid_string(module.usepath())
+            )?;
+            unsplit.write_module(out, module, &externals, &mut VecDeque::new(), lib.name())?;
+        }
+        write_externals(out, lib.name(), &externals)?;
+        write_entity(out, lib.name(), lib.interface())
+    }
+
+    /// When split, `path` is a directory to write one file per page into,
+    /// along with an `index.mmd` linking them.
+    fn render_to_path(&self, lib: &Lib, path: &Path) -> std::io::Result<()> {
+        if self.split.is_none() {
+            let mut file = std::fs::File::create(path)?;
+            return self.render(lib, &mut file);
+        }
+        std::fs::create_dir_all(path)?;
+
+        // The crate's own page only holds the crate's interface and its
+        // relationships to the top-level modules, which all get pages.
+        let root = format!("{}.mmd", file_stem(lib.name()));
+        let mut file = std::fs::File::create(path.join(&root))?;
+        self.write_header(&mut file, lib.name())?;
+        for module in lib.modules() {
+            writeln!(
+                file,
+                "  {} ||--|{{ {} : \"\"",
+                mermaid_string(lib.name()),
+                mermaid_string(module.usepath())
+            )?;
+        }
+        let externals = external_crates(lib);
+        write_externals(&mut file, lib.name(), &externals)?;
+        write_entity(&mut file, lib.name(), lib.interface())?;
+
+        let mut written = vec![(lib.name().to_string(), lib.name().to_string(), root)];
+        let mut pages: VecDeque<Page> = lib
+            .modules()
+            .iter()
+            .map(|module| Page {
+                module,
+                parent: lib.name().into(),
+            })
+            .collect();
+        while let Some(page) = pages.pop_front() {
+            let filename = format!("{}.mmd", file_stem(page.module.usepath()));
+            let mut file = std::fs::File::create(path.join(&filename))?;
+            self.write_header(&mut file, page.module.usepath())?;
+            self.write_module(
+                &mut file,
+                page.module,
+                &externals,
+                &mut pages,
+                page.module.usepath(),
+            )?;
+            written.push((page.module.usepath().into(), page.parent, filename));
+        }
+
+        let mut index = std::fs::File::create(path.join("index.mmd"))?;
+        self.write_index(&mut index, lib, &written)
+    }
+}
+
+/// The number of modules beneath `module`.
+fn descendants(module: &Module) -> usize {
+    module
+        .children()
+        .iter()
+        .map(|child| 1 + descendants(child))
+        .sum()
+}
+
+/// Write a relationship from the module at `usepath` to each external crate
+/// it uses.
+fn write_externals(
+    out: &mut dyn std::io::Write,
+    usepath: &str,
+    externals: &BTreeMap<&str, BTreeSet<&str>>,
+) -> std::io::Result<()> {
+    for name in externals.get(usepath).into_iter().flatten() {
+        writeln!(
+            out,
+            "  {} }}o..o{{ {} : \"uses\"",
+            mermaid_string(usepath),
+            mermaid_string(&format!("extern crate {}", name))
+        )?;
+    }
+    Ok(())
+}
+
+/// Write an entity with one attribute per public identifier. Names that are
+/// not valid mermaid words are also written as the attribute's comment, so
+/// that the original name is not lost.
+fn write_entity(
+    out: &mut dyn std::io::Write,
+    name: &str,
+    interface: &[PublicIdentifier],
+) -> std::io::Result<()> {
+    writeln!(out, "  {} {{", mermaid_string(name))?;
+    for item in interface {
+        let word = mermaid_word(item.name());
+        write!(
+            out,
+            "    {} {}",
+            mermaid_word(&item.kind().to_string()),
+            word
+        )?;
+        if word != item.name() {
+            write!(out, " {}", mermaid_string(item.name()))?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out, "  }}")
+}
@@ -0,0 +1,150 @@
+use crate::render::escape::terminal_text;
+use crate::render::Renderer;
+use crate::{Lib, Module, PublicIdentifier, PublicIdentifierType, Visibility};
+
+/// The module hierarchy in the style of `tree(1)`, listing each module's
+/// public items beneath it.
+#[derive(Default)]
+pub struct Tree {
+    depth: Option<usize>,
+    color: bool,
+}
+
+impl Tree {
+    /// A `depth` of `Some(n)` stops descending after `n` levels; `color`
+    /// enables ANSI colors.
+    pub fn new(depth: Option<usize>, color: bool) -> Self {
+        Self { depth, color }
+    }
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        let text = terminal_text(text);
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", style, text)
+        } else {
+            text.into_owned()
+        }
+    }
+
+    /// Write one level of the tree: the public items of a module followed
+    /// by its child modules, recursing into the latter.
+    fn write_entries(
+        &self,
+        out: &mut dyn std::io::Write,
+        interface: &[PublicIdentifier],
+        children: &[Module],
+        prefix: &str,
+        level: usize,
+    ) -> std::io::Result<()> {
+        if self.depth.is_some_and(|depth| level > depth) {
+            return Ok(());
+        }
+
+        // `pub mod` declarations show up in the interface as well as in the
+        // children; print them once, as a module.
+        let items: Vec<&PublicIdentifier> = interface
+            .iter()
+            .filter(|item| {
+                !(item.kind() == PublicIdentifierType::Mod
+                    && children.iter().any(|module| module.name() == item.name()))
+            })
+            .collect();
+        let count = items.len() + children.len();
+
+        for (index, item) in items.iter().enumerate() {
+            let connector = if index + 1 == count {
+                "└── "
+            } else {
+                "├── "
+            };
+            writeln!(
+                out,
+                "{}{}{} {}",
+                prefix,
+                connector,
+                self.paint(
+                    &item.visibility().to_string(),
+                    visibility_style(item.visibility())
+                ),
+                self.paint(
+                    &format!("{} {}", item.kind(), item.name()),
+                    kind_style(item.kind())
+                ),
+            )?;
+        }
+
+        for (index, module) in children.iter().enumerate() {
+            let last = items.len() + index + 1 == count;
+            let connector = if last { "└── " } else { "├── " };
+            let visibility = interface
+                .iter()
+                .find(|item| {
+                    item.kind() == PublicIdentifierType::Mod && item.name() == module.name()
+                })
+                .map(|item| {
+                    format!(
+                        "{} ",
+                        self.paint(
+                            &item.visibility().to_string(),
+                            visibility_style(item.visibility())
+                        )
+                    )
+                })
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "{}{}{}{}",
+                prefix,
+                connector,
+                visibility,
+                self.paint(
+                    &format!("mod {}", module.name()),
+                    kind_style(PublicIdentifierType::Mod)
+                ),
+            )?;
+            self.write_entries(
+                out,
+                module.interface(),
+                module.children(),
+                &format!("{}{}", prefix, if last { "    " } else { "│   " }),
+                level + 1,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for Tree {
+    fn render(&self, lib: &Lib, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            self.paint(lib.name(), kind_style(PublicIdentifierType::Mod))
+        )?;
+        self.write_entries(out, lib.interface(), lib.modules(), "", 1)
+    }
+}
+
+/// ANSI SGR parameters used when printing a kind of item.
+fn kind_style(kind: PublicIdentifierType) -> &'static str {
+    match kind {
+        PublicIdentifierType::Mod => "1;34",
+        PublicIdentifierType::Struct
+        | PublicIdentifierType::Enum
+        | PublicIdentifierType::Union
+        | PublicIdentifierType::Type => "32",
+        PublicIdentifierType::Trait | PublicIdentifierType::TraitAlias => "35",
+        PublicIdentifierType::Fn => "33",
+        PublicIdentifierType::Const | PublicIdentifierType::Static => "36",
+        PublicIdentifierType::Use | PublicIdentifierType::ExternCrate => "2",
+    }
+}
+
+/// ANSI SGR parameters used when printing a visibility.
+fn visibility_style(visibility: &Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "1;32",
+        Visibility::Crate => "33",
+        Visibility::Super | Visibility::SelfOnly | Visibility::In(_) => "31",
+    }
+}
@@ -0,0 +1,145 @@
+//! Architecture rules: which modules may, and may not, depend on which.
+//!
+//! Rules are read from a TOML file with one `[[rule]]` table per rule:
+//!
+//! ```toml
+//! [[rule]]
+//! from = "crate::domain::**"
+//! forbid = ["crate::infra::**"]
+//!
+//! [[rule]]
+//! from = "crate::api::**"
+//! allow = ["crate::api::**", "crate::domain::**"]
+//! ```
+//!
+//! A rule applies to every dependency of a module matching `from`. Depending
+//! on a module matching one of `forbid` breaks the rule, and so does
+//! depending on a module matching none of `allow`, if `allow` is given.
+use std::path::Path;
+
+use crate::graph::{Dependency, ModuleGraph};
+use crate::pattern::ModulePattern;
+
+/// A single rule, as read from a `[[rule]]` table.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    from: ModulePattern,
+    #[serde(default)]
+    allow: Option<Vec<ModulePattern>>,
+    #[serde(default)]
+    forbid: Vec<ModulePattern>,
+}
+
+impl Rule {
+    fn is_broken_by(&self, dependency: &Dependency, crate_name: &str) -> bool {
+        if !self.from.matches(dependency.from(), crate_name) {
+            return false;
+        }
+        let forbidden = self
+            .forbid
+            .iter()
+            .any(|pattern| pattern.matches(dependency.to(), crate_name));
+        let allowed = self.allow.as_ref().is_none_or(|allow| {
+            allow
+                .iter()
+                .any(|pattern| pattern.matches(dependency.to(), crate_name))
+        });
+        forbidden || !allowed
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.from)?;
+        if !self.forbid.is_empty() {
+            let forbid: Vec<String> = self.forbid.iter().map(ToString::to_string).collect();
+            write!(f, " must not depend on {}", forbid.join(", "))?;
+        }
+        if let Some(allow) = &self.allow {
+            let allow: Vec<String> = allow.iter().map(ToString::to_string).collect();
+            if !self.forbid.is_empty() {
+                write!(f, ", and")?;
+            }
+            write!(f, " may only depend on {}", allow.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A dependency that breaks a rule.
+#[derive(Debug)]
+pub struct Violation<'a, 'r> {
+    rule: &'r Rule,
+    dependency: Dependency<'a>,
+}
+
+impl<'a, 'r> Violation<'a, 'r> {
+    pub fn rule(&self) -> &'r Rule {
+        self.rule
+    }
+
+    pub fn dependency(&self) -> &Dependency<'a> {
+        &self.dependency
+    }
+}
+
+/// The reason rules could not be read.
+#[derive(Debug)]
+pub enum RulesError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+}
+
+impl std::fmt::Display for RulesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RulesError::Io(err) => write!(f, "{}", err),
+            RulesError::Toml(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+/// A set of rules.
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rules {
+    #[serde(default, rename = "rule")]
+    rules: Vec<Rule>,
+}
+
+impl Rules {
+    pub fn from_toml(contents: &str) -> Result<Self, RulesError> {
+        toml::from_str(contents).map_err(RulesError::Toml)
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, RulesError> {
+        let contents = std::fs::read_to_string(path).map_err(RulesError::Io)?;
+        Self::from_toml(&contents)
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Every dependency in `graph` that breaks a rule, once for each rule it
+    /// breaks.
+    pub fn check<'a, 'r>(&'r self, graph: &ModuleGraph<'a>) -> Vec<Violation<'a, 'r>> {
+        let crate_name = graph.root();
+        graph
+            .dependencies()
+            .iter()
+            .flat_map(|dependency| {
+                self.rules
+                    .iter()
+                    .filter(|rule| rule.is_broken_by(dependency, crate_name))
+                    .map(|rule| Violation {
+                        rule,
+                        dependency: *dependency,
+                    })
+            })
+            .collect()
+    }
+}
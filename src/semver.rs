@@ -0,0 +1,376 @@
+//! How the changes between two versions of a crate's public API affect its
+//! version number, along the lines of Cargo's SemVer compatibility guide.
+//!
+//! Removing an item, changing its signature, adding a variant to an enum
+//! that can be matched exhaustively, adding a field to a struct that can be
+//! built outside the crate, and adding a trait item without a default are
+//! major changes. Other additions are minor. Anything else, including no
+//! change to the API at all, is a patch.
+use std::collections::{BTreeMap, HashSet};
+
+use crate::api::{Api, ApiItem, ApiKind};
+
+/// How much of a version number a change needs to bump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl std::fmt::Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::Patch => write!(f, "patch"),
+            Level::Minor => write!(f, "minor"),
+            Level::Major => write!(f, "major"),
+        }
+    }
+}
+
+/// A change to one item of the API.
+#[derive(Debug)]
+pub struct Change<'a> {
+    level: Level,
+    old: Option<&'a ApiItem>,
+    new: Option<&'a ApiItem>,
+    reason: &'static str,
+}
+
+impl<'a> Change<'a> {
+    /// Every change from `old` to `new`, sorted by path. The contents of an
+    /// added or removed item, like the variants of an enum or the items of a
+    /// module, are left out; the item stands for them.
+    pub fn between(old: &'a Api, new: &'a Api) -> Vec<Self> {
+        let old_paths: HashSet<&str> = old.items().iter().map(ApiItem::path).collect();
+        let new_paths: HashSet<&str> = new.items().iter().map(ApiItem::path).collect();
+        // Whether `item` belongs to an item that only `paths` has.
+        let is_contained = |item: &ApiItem, paths: &HashSet<&str>, others: &HashSet<&str>| {
+            let owner = match item.kind() {
+                ApiKind::Impl => Some(item.path()),
+                _ => item.path().rsplit_once("::").map(|(parent, _)| parent),
+            };
+            owner.is_some_and(|owner| paths.contains(owner) && !others.contains(owner))
+        };
+
+        let mut slots = Slots::new();
+        for item in old.items() {
+            slots.entry(slot(item)).or_default().0.push(item);
+        }
+        for item in new.items() {
+            slots.entry(slot(item)).or_default().1.push(item);
+        }
+
+        let mut changes = vec![];
+        for (olds, news) in slots.into_values() {
+            let removed: Vec<&ApiItem> = olds
+                .iter()
+                .filter(|old| !news.iter().any(|new| new.signature() == old.signature()))
+                .copied()
+                .collect();
+            let added: Vec<&ApiItem> = news
+                .iter()
+                .filter(|new| !olds.iter().any(|old| old.signature() == new.signature()))
+                .copied()
+                .collect();
+            match (&removed[..], &added[..]) {
+                ([], []) => {}
+                ([old], [new]) => changes.push(Self::changed(old, new)),
+                _ => {
+                    changes.extend(
+                        removed
+                            .into_iter()
+                            .filter(|old| !is_contained(old, &old_paths, &new_paths))
+                            .map(Self::removed),
+                    );
+                    changes.extend(
+                        added
+                            .into_iter()
+                            .filter(|new| !is_contained(new, &new_paths, &old_paths))
+                            .map(Self::added),
+                    );
+                }
+            }
+        }
+        changes.sort_by(|a, b| a.path().cmp(b.path()));
+        changes
+    }
+
+    fn removed(old: &'a ApiItem) -> Self {
+        Self {
+            level: Level::Major,
+            old: Some(old),
+            new: None,
+            reason: "removed",
+        }
+    }
+
+    fn added(new: &'a ApiItem) -> Self {
+        let (level, reason) = match new.kind() {
+            ApiKind::Variant if new.is_exhaustive() => (
+                Level::Major,
+                "added to an enum that can be matched exhaustively",
+            ),
+            ApiKind::Field if new.is_exhaustive() => (
+                Level::Major,
+                "added to a struct that can be built outside the crate",
+            ),
+            ApiKind::Required => (Level::Major, "added to a trait without a default"),
+            _ => (Level::Minor, "added"),
+        };
+        Self {
+            level,
+            old: None,
+            new: Some(new),
+            reason,
+        }
+    }
+
+    fn changed(old: &'a ApiItem, new: &'a ApiItem) -> Self {
+        let without_default = new
+            .signature()
+            .strip_suffix(" { .. }")
+            .or_else(|| new.signature().strip_suffix(" = .."));
+        let (level, reason) = match (old.kind(), new.kind()) {
+            (ApiKind::Required, ApiKind::Provided) if without_default == Some(old.signature()) => {
+                (Level::Minor, "given a default")
+            }
+            (ApiKind::Provided, ApiKind::Required) => (Level::Major, "lost its default"),
+            _ => (Level::Major, "changed"),
+        };
+        Self {
+            level,
+            old: Some(old),
+            new: Some(new),
+            reason,
+        }
+    }
+
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// The path of the changed item.
+    pub fn path(&self) -> &'a str {
+        self.old.or(self.new).map(ApiItem::path).unwrap_or_default()
+    }
+
+    /// The item before the change, or `None` if it was added.
+    pub fn before(&self) -> Option<&'a ApiItem> {
+        self.old
+    }
+
+    /// The item after the change, or `None` if it was removed.
+    pub fn after(&self) -> Option<&'a ApiItem> {
+        self.new
+    }
+
+    /// What happened to the item, e.g. `removed` or `added to a trait
+    /// without a default`.
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+/// The level of the biggest of `changes`, or a patch if there are none.
+pub fn level(changes: &[Change]) -> Level {
+    changes
+        .iter()
+        .map(Change::level)
+        .max()
+        .unwrap_or(Level::Patch)
+}
+
+/// Which items at a path can be told apart as versions of the same item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Slot<'a> {
+    Item,
+    /// Impls are told apart by their signature; a different one is a
+    /// different impl.
+    Impl(&'a str),
+    Variant,
+    Field,
+    /// A trait item, whether it has a default or not.
+    TraitItem,
+}
+
+/// The old and new items in each slot.
+type Slots<'a> = BTreeMap<(&'a str, Slot<'a>), (Vec<&'a ApiItem>, Vec<&'a ApiItem>)>;
+
+fn slot(item: &ApiItem) -> (&str, Slot<'_>) {
+    let slot = match item.kind() {
+        ApiKind::Item => Slot::Item,
+        ApiKind::Impl => Slot::Impl(item.signature()),
+        ApiKind::Variant => Slot::Variant,
+        ApiKind::Field => Slot::Field,
+        ApiKind::Required | ApiKind::Provided => Slot::TraitItem,
+    };
+    (item.path(), slot)
+}
+
+/// A version number, e.g. `0.2.1`, `1.0.0-beta.1` or `1.2.3+build.5`.
+///
+/// Versions are ordered by SemVer precedence: a pre-release comes before
+/// its release, and its identifiers are compared one by one, numbers
+/// numerically and below any other identifier. Build metadata doesn't
+/// count for precedence, so it only breaks ties.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// The pre-release identifiers after the `-`, e.g. `beta.1`, or empty
+    /// for a release.
+    pub pre: String,
+    /// The build metadata after the `+`, or empty.
+    pub build: String,
+}
+
+/// The part of a version number a change bumps.
+enum Part {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl Version {
+    /// The next version after a change of `level`. Before 1.0.0, Cargo
+    /// treats the first non-zero number as the major version, so a major
+    /// change to `0.2.1` makes `0.3.0` and a minor one `0.2.2`.
+    ///
+    /// A pre-release is followed by its release when that is enough for the
+    /// change: `1.0.0-beta.1` by `1.0.0` for any change, and `1.1.0-rc.1`
+    /// by `1.1.0` for a minor one but `2.0.0` for a major one.
+    pub fn bump(&self, level: Level) -> Self {
+        let (major, minor, patch) = (self.major, self.minor, self.patch);
+        let part = match (major, minor, level) {
+            (0, 0, _) => Part::Patch,
+            (0, _, Level::Major) => Part::Minor,
+            (0, _, _) => Part::Patch,
+            (_, _, Level::Major) => Part::Major,
+            (_, _, Level::Minor) => Part::Minor,
+            (_, _, Level::Patch) => Part::Patch,
+        };
+        let release = !self.pre.is_empty();
+        let (major, minor, patch) = match part {
+            Part::Major if release && minor == 0 && patch == 0 => (major, 0, 0),
+            Part::Major => (major + 1, 0, 0),
+            Part::Minor if release && patch == 0 => (major, minor, 0),
+            Part::Minor => (major, minor + 1, 0),
+            Part::Patch if release => (major, minor, patch),
+            Part::Patch => (major, minor, patch + 1),
+        };
+        Self {
+            major,
+            minor,
+            patch,
+            pre: String::new(),
+            build: String::new(),
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        /// The identifiers of a pre-release, numbers first, or `None` for a
+        /// release, which comes after them all.
+        fn pre(version: &Version) -> Option<Vec<(bool, Option<u64>, &str)>> {
+            (!version.pre.is_empty()).then(|| {
+                version
+                    .pre
+                    .split('.')
+                    .map(|identifier| {
+                        let number = identifier.parse::<u64>().ok();
+                        (number.is_none(), number, identifier)
+                    })
+                    .collect()
+            })
+        }
+        let precedence = |version: &Version| (version.major, version.minor, version.patch);
+        precedence(self)
+            .cmp(&precedence(other))
+            .then_with(|| match (pre(self), pre(other)) {
+                (None, None) => std::cmp::Ordering::Equal,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (Some(ours), Some(theirs)) => ours.cmp(&theirs),
+            })
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::str::FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("{s} is not a version like 1.2.3 or 1.2.3-beta.1");
+        let (rest, build) = s.split_once('+').unwrap_or((s, ""));
+        let (numbers, pre) = rest.split_once('-').unwrap_or((rest, ""));
+        let numbers: Vec<&str> = numbers.split('.').collect();
+        let [major, minor, patch] = numbers[..] else {
+            return Err(error());
+        };
+        // Numbers have no leading zeros.
+        let is_number = |number: &str| {
+            !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+                && (number == "0" || !number.starts_with('0'))
+        };
+        let number = |number: &str| {
+            if !is_number(number) {
+                return Err(error());
+            }
+            number.parse::<u64>().map_err(|_| error())
+        };
+        // Identifiers are non-empty runs of ASCII letters, digits and `-`.
+        let identifiers = |identifiers: &str| {
+            identifiers.is_empty()
+                || identifiers.split('.').all(|identifier| {
+                    !identifier.is_empty()
+                        && identifier
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+                })
+        };
+        // Numeric pre-release identifiers are numbers too.
+        let is_numeric = |identifier: &str| {
+            !identifier.is_empty() && identifier.chars().all(|c| c.is_ascii_digit())
+        };
+        if !identifiers(pre)
+            || pre
+                .split('.')
+                .any(|identifier| is_numeric(identifier) && !is_number(identifier))
+            || !identifiers(build)
+            || (rest.contains('-') && pre.is_empty())
+            || (s.contains('+') && build.is_empty())
+        {
+            return Err(error());
+        }
+        Ok(Self {
+            major: number(major)?,
+            minor: number(minor)?,
+            patch: number(patch)?,
+            pre: pre.into(),
+            build: build.into(),
+        })
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if !self.pre.is_empty() {
+            write!(f, "-{}", self.pre)?;
+        }
+        if !self.build.is_empty() {
+            write!(f, "+{}", self.build)?;
+        }
+        Ok(())
+    }
+}
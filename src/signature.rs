@@ -0,0 +1,388 @@
+//! One line declarations of items, without their attributes, visibility or
+//! bodies, e.g. `fn connect(addr: &str) -> Result<Client, Error>`.
+//!
+//! They are printed from the syntax tree rather than copied from the source,
+//! so reformatting a declaration doesn't change its signature.
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+
+use crate::{Member, MemberKind, Visibility};
+
+/// The signature of `item`, or `None` for a `use` declaration, whose
+/// signature depends on which of its names is meant. The variants of an
+/// enum, the named fields of a struct or union and the items of a trait are
+/// its [members](members), and are left out.
+pub(crate) fn of(item: &syn::Item) -> Option<String> {
+    let non_exhaustive = |attrs: &[syn::Attribute]| {
+        attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("non_exhaustive"))
+            .then(|| quote!(#[non_exhaustive]))
+    };
+    // `{ .. }` marks private fields, which keep the struct from being built
+    // outside its crate.
+    let private_fields = |fields: &syn::FieldsNamed| {
+        fields
+            .named
+            .iter()
+            .any(|field| !matches!(field.vis, syn::Visibility::Public(_)))
+            .then(|| quote!({ .. }))
+    };
+    let tokens = match item {
+        syn::Item::Const(item) => {
+            let (ident, ty) = (&item.ident, &item.ty);
+            quote!(const #ident: #ty)
+        }
+        syn::Item::Enum(item) => {
+            let (ident, generics) = (&item.ident, &item.generics);
+            let where_clause = &generics.where_clause;
+            let non_exhaustive = non_exhaustive(&item.attrs);
+            quote!(#non_exhaustive enum #ident #generics #where_clause)
+        }
+        syn::Item::ExternCrate(item) => {
+            let ident = &item.ident;
+            let rename = item.rename.as_ref().map(|(_, rename)| quote!(as #rename));
+            quote!(extern crate #ident #rename)
+        }
+        syn::Item::Fn(item) => signature(&item.sig),
+        syn::Item::Macro(item) => {
+            let ident = &item.ident;
+            quote!(macro_rules! #ident)
+        }
+        syn::Item::Mod(item) => {
+            let ident = &item.ident;
+            quote!(mod #ident)
+        }
+        syn::Item::Static(item) => {
+            let (mutability, ident, ty) = (&item.mutability, &item.ident, &item.ty);
+            quote!(static #mutability #ident: #ty)
+        }
+        syn::Item::Struct(item) => {
+            let (ident, generics) = (&item.ident, &item.generics);
+            let where_clause = &generics.where_clause;
+            let non_exhaustive = non_exhaustive(&item.attrs);
+            match &item.fields {
+                syn::Fields::Named(named) => {
+                    let private_fields = private_fields(named);
+                    quote!(#non_exhaustive struct #ident #generics #where_clause #private_fields)
+                }
+                // The position of a field is part of its name, so unnamed
+                // fields stay in the signature, with `_` for private ones.
+                syn::Fields::Unnamed(unnamed) => {
+                    let fields = unnamed.unnamed.iter().map(|field| match field.vis {
+                        syn::Visibility::Public(_) => {
+                            let ty = &field.ty;
+                            quote!(pub #ty)
+                        }
+                        _ => quote!(_),
+                    });
+                    quote!(#non_exhaustive struct #ident #generics (#(#fields),*) #where_clause)
+                }
+                syn::Fields::Unit => quote!(#non_exhaustive struct #ident #generics #where_clause),
+            }
+        }
+        syn::Item::Trait(item) => {
+            let (unsafety, auto, ident, generics) = (
+                &item.unsafety,
+                &item.auto_token,
+                &item.ident,
+                &item.generics,
+            );
+            let where_clause = &generics.where_clause;
+            let supertraits = &item.supertraits;
+            let colon = item.colon_token;
+            quote!(#unsafety #auto trait #ident #generics #colon #supertraits #where_clause)
+        }
+        syn::Item::TraitAlias(item) => {
+            let (ident, generics, bounds) = (&item.ident, &item.generics, &item.bounds);
+            let where_clause = &generics.where_clause;
+            quote!(trait #ident #generics = #bounds #where_clause)
+        }
+        syn::Item::Type(item) => {
+            let (ident, generics, ty) = (&item.ident, &item.generics, &item.ty);
+            let where_clause = &generics.where_clause;
+            quote!(type #ident #generics #where_clause = #ty)
+        }
+        syn::Item::Union(item) => {
+            let (ident, generics) = (&item.ident, &item.generics);
+            let where_clause = &generics.where_clause;
+            let private_fields = private_fields(&item.fields);
+            quote!(union #ident #generics #where_clause #private_fields)
+        }
+        _ => return None,
+    };
+    Some(to_string(tokens))
+}
+
+/// The variants of an enum, the named fields of a struct or union, or the
+/// items of a trait.
+pub(crate) fn members(item: &syn::Item) -> Vec<Member> {
+    let field = |field: &syn::Field| {
+        let (ident, ty) = (field.ident.as_ref()?, &field.ty);
+        Some(Member {
+            kind: MemberKind::Field,
+            name: ident.to_string(),
+            visibility: Visibility::from_syn(&field.vis),
+            signature: to_string(quote!(#ident: #ty)),
+            line: ident.span().start().line,
+        })
+    };
+    match item {
+        syn::Item::Enum(item) => item
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                let fields = fields(&variant.fields);
+                let discriminant = variant
+                    .discriminant
+                    .as_ref()
+                    .map(|(_, expr)| quote!(= #expr));
+                Member {
+                    kind: MemberKind::Variant,
+                    name: ident.to_string(),
+                    visibility: Some(Visibility::Public),
+                    signature: to_string(quote!(#ident #fields #discriminant)),
+                    line: ident.span().start().line,
+                }
+            })
+            .collect(),
+        syn::Item::Struct(item) => match &item.fields {
+            syn::Fields::Named(named) => named.named.iter().filter_map(field).collect(),
+            _ => vec![],
+        },
+        syn::Item::Union(item) => item.fields.named.iter().filter_map(field).collect(),
+        syn::Item::Trait(item) => item
+            .items
+            .iter()
+            .filter_map(|item| {
+                // A default is shown as `{ .. }` or `= ..`.
+                let (ident, tokens, provided) = match item {
+                    syn::TraitItem::Const(item) => {
+                        let (ident, ty) = (&item.ident, &item.ty);
+                        let default = item.default.as_ref().map(|_| quote!(= ..));
+                        (ident, quote!(const #ident: #ty #default), default.is_some())
+                    }
+                    syn::TraitItem::Fn(item) => {
+                        let sig = signature(&item.sig);
+                        let default = item.default.as_ref().map(|_| quote!({ .. }));
+                        (&item.sig.ident, quote!(#sig #default), default.is_some())
+                    }
+                    syn::TraitItem::Type(item) => {
+                        let (ident, generics, colon, bounds) =
+                            (&item.ident, &item.generics, &item.colon_token, &item.bounds);
+                        let where_clause = &generics.where_clause;
+                        let default = item.default.as_ref().map(|_| quote!(= ..));
+                        (
+                            ident,
+                            quote!(type #ident #generics #colon #bounds #where_clause #default),
+                            default.is_some(),
+                        )
+                    }
+                    _ => return None,
+                };
+                Some(Member {
+                    kind: if provided {
+                        MemberKind::Provided
+                    } else {
+                        MemberKind::Required
+                    },
+                    name: ident.to_string(),
+                    visibility: Some(Visibility::Public),
+                    signature: to_string(tokens),
+                    line: ident.span().start().line,
+                })
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// The signature of an `impl` block, e.g. `impl<T> From<T> for Client`.
+pub(crate) fn of_impl(item: &syn::ItemImpl) -> String {
+    let (unsafety, generics, self_ty) = (&item.unsafety, &item.generics, &item.self_ty);
+    let where_clause = &generics.where_clause;
+    let r#trait = item
+        .trait_
+        .as_ref()
+        .map(|(bang, path, _)| quote!(#bang #path for));
+    to_string(quote!(#unsafety impl #generics #r#trait #self_ty #where_clause))
+}
+
+/// The signature of an item in an `impl` block, or `None` for a macro.
+pub(crate) fn of_impl_item(item: &syn::ImplItem) -> Option<String> {
+    let tokens = match item {
+        syn::ImplItem::Const(item) => {
+            let (ident, ty) = (&item.ident, &item.ty);
+            quote!(const #ident: #ty)
+        }
+        syn::ImplItem::Fn(item) => signature(&item.sig),
+        syn::ImplItem::Type(item) => {
+            let (ident, generics, ty) = (&item.ident, &item.generics, &item.ty);
+            let where_clause = &generics.where_clause;
+            quote!(type #ident #generics #where_clause = #ty)
+        }
+        _ => return None,
+    };
+    Some(to_string(tokens))
+}
+
+/// A path on one line, e.g. `std::fmt::Display`.
+pub(crate) fn of_path(path: &syn::Path) -> String {
+    to_string(path.to_token_stream())
+}
+
+/// A function's signature, without the `mut` of its arguments, which only
+/// matters to its body.
+fn signature(sig: &syn::Signature) -> TokenStream {
+    let mut sig = sig.clone();
+    for input in sig.inputs.iter_mut() {
+        match input {
+            syn::FnArg::Receiver(receiver) if receiver.reference.is_none() => {
+                receiver.mutability = None;
+            }
+            syn::FnArg::Typed(typed) => {
+                if let syn::Pat::Ident(ident) = typed.pat.as_mut() {
+                    ident.mutability = None;
+                }
+            }
+            syn::FnArg::Receiver(_) => {}
+        }
+    }
+    sig.to_token_stream()
+}
+
+/// The fields of an enum variant.
+fn fields(fields: &syn::Fields) -> TokenStream {
+    match fields {
+        syn::Fields::Named(named) => {
+            let fields = named.named.iter().map(|field| {
+                let (ident, ty) = (&field.ident, &field.ty);
+                quote!(#ident: #ty)
+            });
+            quote!({ #(#fields),* })
+        }
+        syn::Fields::Unnamed(unnamed) => {
+            let fields = unnamed.unnamed.iter().map(|field| &field.ty);
+            quote!((#(#fields),*))
+        }
+        syn::Fields::Unit => quote!(),
+    }
+}
+
+/// `tokens`, spaced the way rustfmt would space them on one line.
+fn to_string(tokens: TokenStream) -> String {
+    let mut out = String::new();
+    write(tokens, &mut out);
+    out.trim().to_string()
+}
+
+/// Whether a word written after `out` needs a space to separate it.
+fn needs_space(out: &str) -> bool {
+    out.ends_with(|c: char| c.is_alphanumeric() || "_>)]}\"".contains(c))
+}
+
+fn write(tokens: TokenStream, out: &mut String) {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                if group.delimiter() == Delimiter::Brace {
+                    if !out.is_empty() && !out.ends_with(' ') {
+                        out.push(' ');
+                    }
+                    let mut inner = String::new();
+                    write(group.stream(), &mut inner);
+                    let inner = inner.trim();
+                    if inner.is_empty() {
+                        out.push_str("{}");
+                    } else {
+                        out.push_str(&format!("{{ {} }}", inner));
+                    }
+                    continue;
+                }
+                // `[` is never an index in a signature, and `(` only follows
+                // a word when it is a function's name or a path.
+                let spaced = match group.delimiter() {
+                    Delimiter::Bracket => needs_space(out),
+                    Delimiter::Parenthesis => ["mut", "dyn", "impl"].iter().any(|keyword| {
+                        out.strip_suffix(keyword).is_some_and(|rest| {
+                            !rest.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+                        })
+                    }),
+                    _ => false,
+                };
+                if spaced {
+                    out.push(' ');
+                }
+                out.push_str(open);
+                write(group.stream(), out);
+                while out.ends_with(' ') {
+                    out.pop();
+                }
+                out.push_str(close);
+            }
+            TokenTree::Ident(ident) => {
+                if needs_space(out) {
+                    out.push(' ');
+                }
+                out.push_str(&ident.to_string());
+            }
+            TokenTree::Literal(literal) => {
+                if needs_space(out) {
+                    out.push(' ');
+                }
+                out.push_str(&literal.to_string());
+            }
+            TokenTree::Punct(punct) => {
+                let mut op = punct.as_char().to_string();
+                let mut spacing = punct.spacing();
+                while spacing == Spacing::Joint && op != "'" {
+                    match tokens.peek() {
+                        Some(TokenTree::Punct(next)) => {
+                            op.push(next.as_char());
+                            spacing = next.spacing();
+                            tokens.next();
+                        }
+                        _ => break,
+                    }
+                }
+                match op.as_str() {
+                    // rustfmt drops a trailing comma on one line, including
+                    // the one ending a `where` clause before a body.
+                    "," if tokens.peek().is_none_or(|next| {
+                        matches!(next, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace)
+                    }) => {}
+                    "," | ";" | ":" => {
+                        while out.ends_with(' ') {
+                            out.pop();
+                        }
+                        out.push_str(&op);
+                        out.push(' ');
+                    }
+                    "->" | "=" | "=>" | "+" | "==" | "!=" | "<=" | ">=" | "||" => {
+                        if !out.is_empty() && !out.ends_with(' ') {
+                            out.push(' ');
+                        }
+                        out.push_str(&op);
+                        out.push(' ');
+                    }
+                    // Prefixes, like in `&'a mut T`, `*const T` or `?Sized`.
+                    "'" | "&" | "&&" | "*" | "!" | "?" | "-" => {
+                        if needs_space(out) {
+                            out.push(' ');
+                        }
+                        out.push_str(&op);
+                    }
+                    _ => out.push_str(&op),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Where a crate's files are read from: the file system, or a revision in the
+//! object database of a git repository.
+use std::collections::BTreeSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Read access to a tree of files.
+pub trait Source {
+    /// The contents of the file at `path`.
+    fn read(&self, path: &Path) -> io::Result<String>;
+
+    fn is_file(&self, path: &Path) -> bool;
+
+    fn is_dir(&self, path: &Path) -> bool;
+
+    /// The files and directories directly in the directory at `path`, in no
+    /// particular order.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+}
+
+/// The files on disk.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileSystem;
+
+impl Source for FileSystem {
+    fn read(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(std::fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect())
+    }
+}
+
+/// The files of a commit in a git repository, read from its object database
+/// with the `git` command, without checking them out. Paths are those the
+/// files would have in the repository's working tree.
+#[derive(Debug)]
+pub struct Git {
+    toplevel: PathBuf,
+    commit: String,
+    /// The files in the commit, relative to `toplevel`.
+    files: BTreeSet<PathBuf>,
+    /// The directories in the commit, relative to `toplevel`, including the
+    /// empty path for `toplevel` itself.
+    dirs: BTreeSet<PathBuf>,
+}
+
+impl Git {
+    /// The files as of `revision`, e.g. `v1.2.0` or `HEAD~3`, in the git
+    /// repository holding the directory at `path`.
+    pub fn open(path: &Path, revision: &str) -> io::Result<Self> {
+        let toplevel = PathBuf::from(git(path, &["rev-parse", "--show-toplevel"])?.trim_end());
+        let commit = git(
+            path,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{revision}^{{commit}}"),
+            ],
+        )
+        .map_err(|_| io::Error::other(format!("{revision} is not a revision")))?
+        .trim_end()
+        .to_string();
+        log::debug!("Reading {revision} ({commit}) from {:?}", toplevel);
+
+        let listing = git(
+            &toplevel,
+            &["ls-tree", "-r", "-z", "--name-only", "--full-tree", &commit],
+        )?;
+        let files: BTreeSet<PathBuf> = listing
+            .split('\0')
+            .filter(|file| !file.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        let dirs = files
+            .iter()
+            .flat_map(|file| file.ancestors().skip(1))
+            .map(Path::to_path_buf)
+            .collect();
+        Ok(Self {
+            toplevel,
+            commit,
+            files,
+            dirs,
+        })
+    }
+
+    /// The directory at the top of the repository's working tree.
+    pub fn toplevel(&self) -> &Path {
+        &self.toplevel
+    }
+
+    /// The full hash of the commit the files are read from.
+    pub fn commit(&self) -> &str {
+        &self.commit
+    }
+
+    /// `path`, relative to the top of the working tree, or `None` if it is
+    /// outside the repository.
+    fn relative<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        path.strip_prefix(&self.toplevel).ok()
+    }
+}
+
+impl Source for Git {
+    fn read(&self, path: &Path) -> io::Result<String> {
+        let relative = self
+            .relative(path)
+            .filter(|relative| self.files.contains(*relative))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} is not in {}", path.display(), self.commit),
+                )
+            })?;
+        // Git always separates the parts of a path with `/`.
+        let relative = relative
+            .iter()
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        git(
+            &self.toplevel,
+            &["cat-file", "blob", &format!("{}:{}", self.commit, relative)],
+        )
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.relative(path)
+            .is_some_and(|relative| self.files.contains(relative))
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.relative(path)
+            .is_some_and(|relative| self.dirs.contains(relative))
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let relative = self
+            .relative(path)
+            .filter(|relative| self.dirs.contains(*relative))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} is not a directory in {}", path.display(), self.commit),
+                )
+            })?;
+        Ok(self
+            .files
+            .iter()
+            .chain(self.dirs.iter())
+            .filter(|entry| !entry.as_os_str().is_empty() && entry.parent() == Some(relative))
+            .map(|entry| self.toplevel.join(entry))
+            .collect())
+    }
+}
+
+/// The output of running `git` with `args` in `dir`. A failing command is an
+/// error holding what it wrote to stderr.
+pub fn git(dir: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|err| io::Error::new(err.kind(), format!("could not run git: {err}")))?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
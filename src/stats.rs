@@ -0,0 +1,247 @@
+//! The size and complexity of a module's source.
+use std::collections::BTreeMap;
+
+use syn::visit::Visit;
+
+/// Lines of a module's source, by what they hold. A line holding both code
+/// and a comment counts as code.
+#[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct Lines {
+    pub total: usize,
+    pub code: usize,
+    pub comment: usize,
+    pub blank: usize,
+}
+
+impl Lines {
+    fn count(contents: &str) -> Self {
+        let mut lines = Self::default();
+        // How deep in nested block comments the line starts.
+        let mut depth = 0;
+        for line in contents.lines() {
+            lines.total += 1;
+            let (is_code, is_comment) = scan(line, &mut depth);
+            if is_code {
+                lines.code += 1;
+            } else if is_comment {
+                lines.comment += 1;
+            } else {
+                lines.blank += 1;
+            }
+        }
+        lines
+    }
+}
+
+/// Whether `line` holds code and whether it holds a comment, starting
+/// `depth` block comments deep and leaving `depth` as it ends. A string
+/// literal is taken to end on the line it starts on.
+fn scan(line: &str, depth: &mut usize) -> (bool, bool) {
+    let (mut is_code, mut is_comment) = (false, *depth > 0);
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('/', Some('*')) => {
+                chars.next();
+                *depth += 1;
+                is_comment = true;
+            }
+            ('*', Some('/')) if *depth > 0 => {
+                chars.next();
+                *depth -= 1;
+            }
+            _ if *depth > 0 => {}
+            ('/', Some('/')) => return (is_code, true),
+            ('"', _) => {
+                is_code = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            (c, _) if !c.is_whitespace() => is_code = true,
+            _ => {}
+        }
+    }
+    (is_code, is_comment)
+}
+
+/// The size and complexity of a module. Only the module's own files count,
+/// not those of its children; inline modules count towards the module that
+/// holds them.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct Stats {
+    lines: Lines,
+    items: BTreeMap<String, usize>,
+    complexities: Vec<usize>,
+}
+
+impl Stats {
+    pub(crate) fn from_file(contents: &str, file: &syn::File) -> Self {
+        let mut items = BTreeMap::new();
+        count_items(&file.items, &mut items);
+
+        let mut functions = Functions {
+            complexities: vec![],
+        };
+        functions.visit_file(file);
+
+        Self {
+            lines: Lines::count(contents),
+            items,
+            complexities: functions.complexities,
+        }
+    }
+
+    pub fn lines(&self) -> Lines {
+        self.lines
+    }
+
+    /// The number of items of each kind (`"fn"`, `"struct"`, `"impl"`, ...)
+    /// at the top level of the module and its inline modules, whatever their
+    /// visibility.
+    pub fn items(&self) -> &BTreeMap<String, usize> {
+        &self.items
+    }
+
+    /// The number of functions and methods with a body.
+    pub fn functions(&self) -> usize {
+        self.complexities.len()
+    }
+
+    /// The cyclomatic complexity of each function and method with a body.
+    pub fn complexities(&self) -> &[usize] {
+        &self.complexities
+    }
+
+    pub fn average_complexity(&self) -> f64 {
+        match self.complexities.len() {
+            0 => 0.0,
+            functions => self.complexities.iter().sum::<usize>() as f64 / functions as f64,
+        }
+    }
+
+    pub fn max_complexity(&self) -> usize {
+        self.complexities.iter().copied().max().unwrap_or_default()
+    }
+}
+
+/// Count `items` by kind into `counts`, along with the items of inline
+/// modules among them.
+fn count_items(items: &[syn::Item], counts: &mut BTreeMap<String, usize>) {
+    for item in items {
+        let kind = match item {
+            syn::Item::Const(_) => "const",
+            syn::Item::Enum(_) => "enum",
+            syn::Item::ExternCrate(_) => "extern crate",
+            syn::Item::Fn(_) => "fn",
+            syn::Item::ForeignMod(_) => "extern",
+            syn::Item::Impl(_) => "impl",
+            syn::Item::Macro(_) => "macro",
+            syn::Item::Mod(_) => "mod",
+            syn::Item::Static(_) => "static",
+            syn::Item::Struct(_) => "struct",
+            syn::Item::Trait(_) => "trait",
+            syn::Item::TraitAlias(_) => "trait alias",
+            syn::Item::Type(_) => "type",
+            syn::Item::Union(_) => "union",
+            syn::Item::Use(_) => "use",
+            _ => continue,
+        };
+        *counts.entry(kind.to_string()).or_insert(0) += 1;
+        if let syn::Item::Mod(syn::ItemMod {
+            content: Some((_, items)),
+            ..
+        }) = item
+        {
+            count_items(items, counts);
+        }
+    }
+}
+
+/// Collects the complexity of every function and method with a body,
+/// including those declared inside other functions.
+struct Functions {
+    complexities: Vec<usize>,
+}
+
+impl Functions {
+    fn add(&mut self, body: &syn::Block) {
+        let mut complexity = Complexity { complexity: 1 };
+        complexity.visit_block(body);
+        self.complexities.push(complexity.complexity);
+    }
+}
+
+impl<'ast> Visit<'ast> for Functions {
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        self.add(&item.block);
+        syn::visit::visit_item_fn(self, item);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        self.add(&item.block);
+        syn::visit::visit_impl_item_fn(self, item);
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast syn::TraitItemFn) {
+        if let Some(body) = &item.default {
+            self.add(body);
+        }
+        syn::visit::visit_trait_item_fn(self, item);
+    }
+}
+
+/// Cyclomatic complexity: one, plus one for every branch.
+struct Complexity {
+    complexity: usize,
+}
+
+impl<'ast> Visit<'ast> for Complexity {
+    fn visit_expr_if(&mut self, expr: &'ast syn::ExprIf) {
+        self.complexity += 1;
+        syn::visit::visit_expr_if(self, expr);
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast syn::ExprWhile) {
+        self.complexity += 1;
+        syn::visit::visit_expr_while(self, expr);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.complexity += 1;
+        syn::visit::visit_expr_for_loop(self, expr);
+    }
+
+    fn visit_expr_match(&mut self, expr: &'ast syn::ExprMatch) {
+        self.complexity += expr.arms.len().saturating_sub(1);
+        syn::visit::visit_expr_match(self, expr);
+    }
+
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        if arm.guard.is_some() {
+            self.complexity += 1;
+        }
+        syn::visit::visit_arm(self, arm);
+    }
+
+    fn visit_bin_op(&mut self, op: &'ast syn::BinOp) {
+        if matches!(op, syn::BinOp::And(_) | syn::BinOp::Or(_)) {
+            self.complexity += 1;
+        }
+    }
+
+    fn visit_expr_try(&mut self, expr: &'ast syn::ExprTry) {
+        self.complexity += 1;
+        syn::visit::visit_expr_try(self, expr);
+    }
+
+    fn visit_item(&mut self, _item: &'ast syn::Item) {
+        // Nested items are counted on their own.
+    }
+}
@@ -0,0 +1,140 @@
+//! Where each item in a crate's interface is used from.
+//!
+//! An item is used wherever a `use` declaration or a path of more than one
+//! segment refers to it, or to something inside it. A glob import uses
+//! every item of the module it imports from. Uses through method calls,
+//! type inference or a single-segment path after a glob import are not
+//! seen, so an item without uses may still be used that way.
+use std::collections::HashMap;
+
+use crate::{Lib, Module, PublicIdentifier, Reference};
+
+/// An item in a module's interface, and the references to it from outside
+/// that module.
+#[derive(Debug)]
+pub struct Usage<'a> {
+    module: &'a str,
+    item: &'a PublicIdentifier,
+    modules: Vec<&'a str>,
+    external: Vec<&'a Reference>,
+}
+
+impl<'a> Usage<'a> {
+    /// The usage of every item in the interfaces of `lib`'s modules, except
+    /// for glob re-exports. `external` holds references from outside the
+    /// crate, e.g. from [`Reference::find_in_path`] on its `tests/`.
+    pub fn of(lib: &'a Lib, external: &'a [Reference]) -> Vec<Self> {
+        fn collect<'a>(
+            module: &'a Module,
+            modules: &mut Vec<(&'a str, &'a [PublicIdentifier], &'a [Reference])>,
+        ) {
+            modules.push((module.usepath(), module.interface(), module.references()));
+            module
+                .children()
+                .iter()
+                .for_each(|child| collect(child, modules));
+        }
+        let mut modules = vec![(lib.name(), lib.interface(), lib.references())];
+        lib.modules()
+            .iter()
+            .for_each(|module| collect(module, &mut modules));
+
+        let mut usages: Vec<Usage> = modules
+            .iter()
+            .flat_map(|(module, interface, _)| {
+                interface
+                    .iter()
+                    .filter(|item| item.name() != "*")
+                    .map(|item| Usage {
+                        module,
+                        item,
+                        modules: vec![],
+                        external: vec![],
+                    })
+            })
+            .collect();
+        let index: HashMap<(&str, &str), usize> = usages
+            .iter()
+            .enumerate()
+            .map(|(index, usage)| ((usage.module, usage.item.name()), index))
+            .collect();
+
+        // The items a path refers to: for every prefix of it that is a
+        // module, the item named by the next segment, or every item of the
+        // module for a glob. `crate::net::Client` uses both `net` and
+        // `Client`.
+        let referred = |reference: &Reference| -> Vec<usize> {
+            let path = reference.path();
+            (1..path.len())
+                .flat_map(|length| {
+                    let module = path[..length].join("::");
+                    if path[length] == "*" {
+                        usages
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, usage)| usage.module == module)
+                            .map(|(index, _)| index)
+                            .collect()
+                    } else {
+                        index
+                            .get(&(module.as_str(), path[length].as_str()))
+                            .into_iter()
+                            .copied()
+                            .collect::<Vec<usize>>()
+                    }
+                })
+                .collect()
+        };
+
+        let mut used: Vec<(usize, Option<&'a str>, &'a Reference)> = vec![];
+        for (module, _, references) in modules.iter() {
+            for reference in references.iter() {
+                for found in referred(reference) {
+                    used.push((found, Some(module), reference));
+                }
+            }
+        }
+        for reference in external.iter() {
+            for found in referred(reference) {
+                used.push((found, None, reference));
+            }
+        }
+        for (found, module, reference) in used {
+            let usage = &mut usages[found];
+            match module {
+                Some(module) if module == usage.module => {}
+                Some(module) => {
+                    if !usage.modules.contains(&module) {
+                        usage.modules.push(module);
+                    }
+                }
+                None => usage.external.push(reference),
+            }
+        }
+        usages
+    }
+
+    /// The `usepath` of the module whose interface holds the item.
+    pub fn module(&self) -> &'a str {
+        self.module
+    }
+
+    pub fn item(&self) -> &'a PublicIdentifier {
+        self.item
+    }
+
+    /// The `usepath`s of the other modules in the crate referring to the item.
+    pub fn modules(&self) -> &[&'a str] {
+        &self.modules
+    }
+
+    /// The references to the item from outside the crate.
+    pub fn external(&self) -> &[&'a Reference] {
+        &self.external
+    }
+
+    /// Whether nothing outside the item's own module refers to it.
+    pub fn is_unused(&self) -> bool {
+        self.modules.is_empty() && self.external.is_empty()
+    }
+}
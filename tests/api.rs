@@ -0,0 +1,130 @@
+//! Tests for listing the public API, writing it to a snapshot and checking
+//! the crate against one.
+use std::path::{Path, PathBuf};
+
+use thigpen::api::Api;
+use thigpen::Lib;
+
+mod common;
+
+use common::{stdout, TempDir};
+
+const GRAPH_API: &str = "\
+graph::a: mod a
+graph::a::A: struct A(pub B)
+graph::b: mod b
+graph::b::B: struct B(pub C)
+graph::c: mod c
+graph::c::C: struct C
+graph::c::make: fn make() -> crate::a::A
+graph::d: mod d
+graph::d::run: fn run(_: A)
+graph::parent: mod parent
+graph::parent::Error: struct Error
+graph::parent::Thing: struct Thing(pub Error)
+";
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/graph/src")
+}
+
+#[test]
+fn api_of_a_crate() {
+    let lib = Lib::from_path("graph", &fixture().join("lib.rs"));
+    let api = Api::of(&lib);
+    assert_eq!(api.to_string(), GRAPH_API);
+
+    // `Thing` is only reachable through its re-export.
+    assert!(api
+        .items()
+        .iter()
+        .all(|item| !item.path().contains("child")));
+
+    let json = serde_json::to_string(&api).unwrap();
+    let read: Api = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.to_string(), GRAPH_API);
+}
+
+/// A copy of the `graph` fixture with a manifest.
+fn graph_crate(name: &str) -> TempDir {
+    let crate_ = TempDir::new(name);
+    crate_.copy(&fixture(), "src");
+    crate_.manifest(".", "graph", "0.1.0");
+    crate_
+}
+
+#[test]
+fn api_snapshot_and_check() {
+    let crate_ = graph_crate("api-check");
+    for (snapshot, format) in [("api.txt", "table"), ("api.json", "json")] {
+        let output = crate_.thigpen(&["api", "--snapshot", snapshot, "--format", format]);
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(
+            stdout(&output),
+            format!("Wrote 12 items of the API of graph to {snapshot}\n")
+        );
+
+        let output = crate_.thigpen(&["api", "--snapshot", snapshot, "--check"]);
+        assert_eq!(output.status.code(), Some(0));
+        assert_eq!(
+            stdout(&output),
+            format!("The API of graph matches {snapshot}\n")
+        );
+    }
+    assert_eq!(
+        std::fs::read_to_string(crate_.path().join("api.txt")).unwrap(),
+        GRAPH_API
+    );
+
+    crate_.write("src/d.rs", "pub fn run(_: u8) {}\n");
+    for snapshot in ["api.txt", "api.json"] {
+        let output = crate_.thigpen(&["api", "--snapshot", snapshot, "--check"]);
+        assert_eq!(output.status.code(), Some(1));
+        let diff = stdout(&output);
+        assert!(
+            diff.starts_with(&format!(
+                "--- {snapshot}\n+++ {snapshot} (current)\n@@ -6,7 +6,7 @@\n"
+            )),
+            "{diff}"
+        );
+        assert!(diff.contains("\n-graph::d::run: fn run(_: A)\n+graph::d::run: fn run(_: u8)\n"));
+    }
+
+    let output = crate_.thigpen(&["api", "--snapshot", "missing.txt", "--check"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn api_of_reexported_modules() {
+    let crate_ = TempDir::new("api-reexported-modules");
+    crate_.write(
+        "src/lib.rs",
+        "\
+mod private {
+    pub mod inner {
+        pub fn f() {}
+    }
+}
+pub mod m2 {
+    pub fn h() {}
+}
+pub use m2 as renamed;
+pub use self::private::inner::{self};
+pub use private::inner::{self as other};
+",
+    );
+    let lib = Lib::from_path("t3", &crate_.path().join("src/lib.rs"));
+    assert_eq!(
+        Api::of(&lib).to_string(),
+        "\
+t3::inner: mod inner
+t3::inner::f: fn f()
+t3::m2: mod m2
+t3::m2::h: fn h()
+t3::other: mod inner
+t3::other::f: fn f()
+t3::renamed: mod m2
+t3::renamed::h: fn h()
+"
+    );
+}
@@ -0,0 +1,36 @@
+//! Commands whose output is piped into something that stops reading early,
+//! like `head`, stop quietly instead of panicking.
+use std::process::{Command, Stdio};
+
+fn run_with_closed_stdout(args: &[&str]) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_thigpen"))
+        .arg(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    drop(child.stdout.take());
+    let output = child.wait_with_output().unwrap();
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("panicked"), "{args:?}: {stderr}");
+    assert!(!stderr.contains("Could not write"), "{args:?}: {stderr}");
+    assert_ne!(output.status.code(), Some(101), "{args:?}");
+}
+
+#[test]
+fn commands_stop_quietly() {
+    for args in [
+        &["api"][..],
+        &["api", "--format", "json"],
+        &["stats"],
+        &["metrics", "--format", "json"],
+        &["cycles"],
+        &["unused"],
+        &["--output-type", "tree"],
+        &["--output-type", "json"],
+    ] {
+        run_with_closed_stdout(args);
+    }
+}
@@ -0,0 +1,121 @@
+//! What the integration tests share: crates and git repositories written to
+//! a directory of their own, and running `thigpen` on them.
+// Each test uses only some of this.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A directory in the temporary directory, removed when dropped.
+pub struct TempDir {
+    dir: PathBuf,
+}
+
+impl TempDir {
+    /// An empty directory; `name` keeps tests running at the same time apart.
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("thigpen-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self { dir }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write `contents` to `file`, relative to the directory, creating the
+    /// directories above it.
+    pub fn write(&self, file: &str, contents: &str) {
+        let path = self.dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    /// Write a `Cargo.toml` for the package `name` at `version` into `dir`,
+    /// relative to the directory.
+    pub fn manifest(&self, dir: &str, name: &str, version: &str) {
+        self.write(
+            &format!("{dir}/Cargo.toml"),
+            &format!("[package]\nname = \"{name}\"\nversion = \"{version}\"\nedition = \"2021\"\n"),
+        );
+    }
+
+    /// Copy the files under `from` into `to`, relative to the directory.
+    pub fn copy(&self, from: &Path, to: &str) {
+        fn copy(from: &Path, to: &Path) {
+            std::fs::create_dir_all(to).unwrap();
+            for entry in std::fs::read_dir(from).unwrap() {
+                let path = entry.unwrap().path();
+                let target = to.join(path.file_name().unwrap());
+                if path.is_dir() {
+                    copy(&path, &target);
+                } else {
+                    std::fs::copy(&path, &target).unwrap();
+                }
+            }
+        }
+        copy(from, &self.dir.join(to));
+    }
+
+    /// Run `git` with `args` in the directory, as a made-up user.
+    pub fn git(&self, args: &[&str]) {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=thigpen",
+                "-c",
+                "user.email=thigpen@example.com",
+            ])
+            .args(args)
+            .current_dir(&self.dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// Commit every file and tag the commit, making the directory a git
+    /// repository first if it isn't one.
+    pub fn commit(&self, tag: &str) {
+        if !self.dir.join(".git").exists() {
+            self.git(&["init", "--quiet"]);
+        }
+        self.git(&["add", "--all"]);
+        self.git(&["commit", "--quiet", "--message", tag]);
+        self.git(&["tag", tag]);
+    }
+
+    /// Run `thigpen` on the crate in the directory, from the directory.
+    pub fn thigpen(&self, args: &[&str]) -> Output {
+        self.thigpen_in("", args)
+    }
+
+    /// Run `thigpen` on the crate in `dir`, relative to the directory, from
+    /// the directory.
+    pub fn thigpen_in(&self, dir: &str, args: &[&str]) -> Output {
+        let path = match dir {
+            "" => self.dir.clone(),
+            dir => self.dir.join(dir),
+        };
+        Command::new(env!("CARGO_BIN_EXE_thigpen"))
+            .arg(path)
+            .args(args)
+            .current_dir(&self.dir)
+            .output()
+            .unwrap()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
@@ -0,0 +1,57 @@
+//! Tests for finding dependencies that are unused, or declared in the wrong
+//! table of the manifest.
+mod common;
+
+use common::{stdout, TempDir};
+
+#[test]
+fn deps_reports_misplaced_and_unused_dependencies() {
+    let crate_ = TempDir::new("deps-misplaced");
+    crate_.write(
+        "Cargo.toml",
+        r#"[package]
+name = "sample"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+for-build = "1"
+for-src = "1"
+for-tests = "1"
+unused = "1"
+
+[dev-dependencies]
+unused-dev = "1"
+"#,
+    );
+    crate_.write("src/lib.rs", "pub fn run() {\n    for_src::run();\n}\n");
+    crate_.write(
+        "tests/run.rs",
+        "#[test]\nfn run() {\n    for_tests::run();\n}\n",
+    );
+    crate_.write("build.rs", "fn main() {\n    for_build::run();\n}\n");
+
+    let output = crate_.thigpen(&["deps"]);
+    assert_eq!(
+        stdout(&output),
+        "\
+[dependencies] for_build: only used by build.rs; move it to [build-dependencies]
+[dependencies] for_tests: only used by tests, benches or examples; move it to [dev-dependencies]
+[dependencies] unused: never used
+[dev-dependencies] unused_dev: never used
+"
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    crate_.write(
+        "Cargo.toml",
+        "[package]\nname = \"sample\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+         [dependencies]\nfor-src = \"1\"\n",
+    );
+    let output = crate_.thigpen(&["deps"]);
+    assert_eq!(
+        stdout(&output),
+        "Every dependency of sample is used where it is declared\n"
+    );
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -0,0 +1,31 @@
+//! Tests for the problems found while reading a crate's modules.
+//!
+//! The `ambiguous` fixture has both `foo.rs` and `foo/mod.rs` for its module
+//! `foo`, which rustc refuses (E0761).
+use std::path::Path;
+
+use thigpen::{DiagnosticKind, Lib};
+
+fn fixture() -> Lib {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ambiguous/src/lib.rs");
+    Lib::from_path("ambiguous", &path)
+}
+
+#[test]
+fn ambiguous_files() {
+    let lib = fixture();
+    let diagnostics = lib.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind(), DiagnosticKind::AmbiguousFile);
+    assert_eq!(diagnostics[0].usepath(), "ambiguous::foo");
+    assert_eq!(diagnostics[0].line(), 2);
+    let [mod_rs, rs] = diagnostics[0].candidates();
+    assert!(mod_rs.ends_with("src/foo/mod.rs"));
+    assert!(rs.ends_with("src/foo.rs"));
+
+    // Only `foo.rs` is read.
+    let foo = &lib.modules()[0];
+    assert!(foo.file().unwrap().ends_with("src/foo.rs"));
+    let names: Vec<&str> = foo.interface().iter().map(|item| item.name()).collect();
+    assert_eq!(names, ["from_rs"]);
+}
@@ -0,0 +1,81 @@
+//! Tests for suggesting narrower visibilities, on the `exposure` fixture.
+//!
+//! Its private module `hidden` has items used from nowhere, from a sibling
+//! module and from the crate root, and modules re-exported by a glob, under
+//! another name and with `{self}`.
+use std::path::Path;
+
+use thigpen::exposure::Suggestion;
+use thigpen::Lib;
+
+fn fixture() -> Lib {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/exposure/src/lib.rs");
+    Lib::from_path("exposure", &path)
+}
+
+/// Each suggestion as the item's path and the visibility it can have.
+fn suggestions(lib: &Lib) -> Vec<(String, String)> {
+    Suggestion::of(lib, &[])
+        .iter()
+        .map(|suggestion| {
+            let usage = suggestion.usage();
+            (
+                format!("{}::{}", usage.module(), usage.item().name()),
+                match suggestion.visibility() {
+                    Some(visibility) => visibility.to_string(),
+                    None => "private".into(),
+                },
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn suggestions_narrow_to_the_users() {
+    let lib = fixture();
+    let suggestions = suggestions(&lib);
+    let suggested = |path: &str| {
+        suggestions
+            .iter()
+            .find(|(suggested, _)| suggested == path)
+            .map(|(_, visibility)| visibility.as_str())
+    };
+    assert_eq!(suggested("exposure::hidden::a::private"), Some("private"));
+    assert_eq!(
+        suggested("exposure::hidden::a::for_parent"),
+        Some("pub(super)")
+    );
+    assert_eq!(
+        suggested("exposure::hidden::a::for_crate"),
+        Some("pub(crate)")
+    );
+    // `pub(super)` in a top-level module would be the same as `pub(crate)`.
+    assert_eq!(suggested("exposure::hidden::a"), Some("pub(crate)"));
+    // Already as narrow as its users allow.
+    assert_eq!(suggested("exposure::hidden::a::needed"), None);
+    // Only its items are re-exported.
+    assert_eq!(suggested("exposure::hidden::globbed"), Some("pub(crate)"));
+    assert_eq!(suggestions.len(), 6, "{suggestions:?}");
+}
+
+#[test]
+fn reexported_modules_are_reachable() {
+    let lib = fixture();
+    let suggestions = suggestions(&lib);
+    for reachable in [
+        // Through `pub use hidden::globbed::*`.
+        "exposure::hidden::globbed::deep",
+        "exposure::hidden::globbed::deep::deep",
+        // Through `pub use hidden::renamed as other`.
+        "exposure::hidden::renamed",
+        "exposure::hidden::renamed::renamed",
+        // Through `pub use hidden::itself::{self}`.
+        "exposure::hidden::itself",
+        "exposure::hidden::itself::itself",
+    ] {
+        assert!(
+            suggestions.iter().all(|(path, _)| path != reachable),
+            "{reachable}: {suggestions:?}"
+        );
+    }
+}
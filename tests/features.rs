@@ -0,0 +1,92 @@
+//! Tests for reading what features a predicate needs, and what each feature
+//! does to the `features` fixture.
+use std::path::Path;
+
+use thigpen::cfg::Cfg;
+use thigpen::features::{FeatureMap, Gated};
+use thigpen::Lib;
+
+fn fixture() -> Lib {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/features/src/lib.rs");
+    Lib::from_path("features", &path)
+}
+
+fn feature(name: &str) -> Cfg {
+    Cfg::Feature(name.into())
+}
+
+fn not(cfg: Cfg) -> Cfg {
+    Cfg::Not(Box::new(cfg))
+}
+
+#[test]
+fn features_of_predicates() {
+    let net_or_tls = Cfg::Any(vec![feature("net"), feature("tls")]);
+    assert!(net_or_tls.required_features().is_empty());
+    assert!(net_or_tls.excluded_features().is_empty());
+    assert_eq!(net_or_tls.features(), ["net", "tls"]);
+
+    // Either feature is enough for `any`, so neither may be enabled.
+    let offline = not(net_or_tls);
+    assert!(offline.required_features().is_empty());
+    assert_eq!(offline.excluded_features(), ["net", "tls"]);
+
+    let plain = Cfg::All(vec![feature("net"), not(feature("tls"))]);
+    assert_eq!(plain.required_features(), ["net"]);
+    assert_eq!(plain.excluded_features(), ["tls"]);
+
+    // Neither feature is enough for `all`.
+    let secure = not(Cfg::All(vec![feature("net"), feature("tls")]));
+    assert!(secure.excluded_features().is_empty());
+
+    let unix = Cfg::Other("unix".into());
+    let net_on_unix = Cfg::All(vec![unix.clone(), feature("net")]);
+    assert_eq!(net_on_unix.required_features(), ["net"]);
+    assert!(unix.features().is_empty());
+}
+
+/// What enabling the feature `name` adds, removes and alters, by path.
+fn effects(map: &FeatureMap, name: &str) -> [Vec<String>; 3] {
+    let paths = |gated: &[Gated]| {
+        gated
+            .iter()
+            .map(|gated| match gated {
+                Gated::Module(module) => module.usepath().to_string(),
+                Gated::Item(module, item) => format!("{}::{}", module, item.name()),
+            })
+            .collect()
+    };
+    let feature = map.get(name).unwrap();
+    [
+        paths(feature.adds()),
+        paths(feature.removes()),
+        paths(feature.alters()),
+    ]
+}
+
+#[test]
+fn feature_map() {
+    let lib = fixture();
+    let map = FeatureMap::of(&lib);
+    assert_eq!(
+        map.features().map(|(name, _)| name).collect::<Vec<_>>(),
+        ["net", "serde", "tls"]
+    );
+    assert_eq!(
+        effects(&map, "net"),
+        [
+            vec!["features::plain", "features::net"],
+            vec!["features::offline"],
+            vec![],
+        ]
+    );
+    assert_eq!(
+        effects(&map, "tls"),
+        [vec![], vec!["features::offline", "features::plain"], vec![]]
+    );
+    assert_eq!(
+        effects(&map, "serde"),
+        [vec![], vec![], vec!["features::Config"]]
+    );
+    assert!(map.get("other").is_none());
+}
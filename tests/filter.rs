@@ -0,0 +1,122 @@
+//! Tests for pruning a crate with a filter, on the `graph` fixture.
+use std::path::Path;
+
+use thigpen::filter::Filter;
+use thigpen::pattern::ModulePattern;
+use thigpen::{Lib, Module, PublicIdentifierType};
+
+fn fixture() -> Lib {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/graph/src/lib.rs");
+    Lib::from_path("graph", &path)
+}
+
+fn patterns(patterns: &[&str]) -> Vec<ModulePattern> {
+    patterns
+        .iter()
+        .map(|pattern| ModulePattern::new(pattern).unwrap())
+        .collect()
+}
+
+/// The `usepath` of every module left, beneath the root.
+fn modules(lib: &Lib) -> Vec<&str> {
+    fn walk<'a>(module: &'a Module, modules: &mut Vec<&'a str>) {
+        modules.push(module.usepath());
+        module
+            .children()
+            .iter()
+            .for_each(|child| walk(child, modules));
+    }
+    let mut modules = vec![];
+    lib.modules()
+        .iter()
+        .for_each(|module| walk(module, &mut modules));
+    modules
+}
+
+#[test]
+fn filter_keeps_everything_by_default() {
+    let lib = Filter::default().apply(fixture()).unwrap();
+    assert_eq!(
+        modules(&lib),
+        [
+            "graph::a",
+            "graph::b",
+            "graph::c",
+            "graph::d",
+            "graph::parent",
+            "graph::parent::child"
+        ]
+    );
+}
+
+#[test]
+fn filter_root() {
+    let lib = Filter::default()
+        .root("crate::parent")
+        .apply(fixture())
+        .unwrap();
+    assert_eq!(lib.name(), "graph::parent");
+    assert_eq!(modules(&lib), ["graph::parent::child"]);
+    let interface: Vec<&str> = lib.interface().iter().map(|item| item.name()).collect();
+    assert_eq!(interface, ["Thing", "Error"]);
+
+    let err = Filter::default()
+        .root("crate::missing")
+        .apply(fixture())
+        .unwrap_err();
+    assert_eq!(err.to_string(), "there is no module crate::missing");
+}
+
+#[test]
+fn filter_depth() {
+    let lib = Filter::default().depth(1).apply(fixture()).unwrap();
+    assert_eq!(
+        modules(&lib),
+        [
+            "graph::a",
+            "graph::b",
+            "graph::c",
+            "graph::d",
+            "graph::parent"
+        ]
+    );
+}
+
+#[test]
+fn filter_include_and_exclude() {
+    // The modules above an included module stay, to hold it.
+    let lib = Filter::default()
+        .include(patterns(&["crate::**::child", "crate::a"]))
+        .apply(fixture())
+        .unwrap();
+    assert_eq!(
+        modules(&lib),
+        ["graph::a", "graph::parent", "graph::parent::child"]
+    );
+    let interface: Vec<&str> = lib.interface().iter().map(|item| item.name()).collect();
+    assert_eq!(interface, ["a", "parent"]);
+
+    // The modules beneath an excluded module go with it.
+    let lib = Filter::default()
+        .exclude(patterns(&["crate::parent", "crate::c"]))
+        .apply(fixture())
+        .unwrap();
+    assert_eq!(modules(&lib), ["graph::a", "graph::b", "graph::d"]);
+}
+
+#[test]
+fn filter_kinds() {
+    let lib = Filter::default()
+        .kinds(vec![PublicIdentifierType::Struct])
+        .apply(fixture())
+        .unwrap();
+    let parent = &lib.modules()[4];
+    let interface: Vec<_> = parent
+        .interface()
+        .iter()
+        .map(|item| (item.kind(), item.name()))
+        .collect();
+    assert_eq!(interface, [(PublicIdentifierType::Struct, "Error")]);
+    // `pub mod` declarations stay along with their modules.
+    assert_eq!(lib.interface().len(), 5);
+}
@@ -0,0 +1 @@
+pub fn from_rs() {}
@@ -0,0 +1,2 @@
+//! A module with both of the files rustc looks for.
+pub mod foo;
@@ -0,0 +1,40 @@
+//! Items more visible than the modules using them need, next to items that
+//! are only part of the API through re-exports.
+mod hidden {
+    pub mod a {
+        pub fn private() {}
+        pub fn for_parent() {}
+        pub fn for_crate() {}
+        pub(crate) fn needed() {}
+    }
+
+    pub mod b {
+        pub(crate) fn run() {
+            super::a::for_parent();
+        }
+    }
+
+    pub mod globbed {
+        pub mod deep {
+            pub fn deep() {}
+        }
+    }
+
+    pub mod renamed {
+        pub fn renamed() {}
+    }
+
+    pub mod itself {
+        pub fn itself() {}
+    }
+}
+
+pub use hidden::globbed::*;
+pub use hidden::itself::{self};
+pub use hidden::renamed as other;
+
+pub fn run() {
+    hidden::a::for_crate();
+    hidden::a::needed();
+    hidden::b::run();
+}
@@ -0,0 +1,15 @@
+//! Items gated by features, with every kind of predicate.
+#[cfg(feature = "net")]
+pub mod net;
+
+#[cfg(not(any(feature = "net", feature = "tls")))]
+pub fn offline() {}
+
+#[cfg(all(feature = "net", not(feature = "tls")))]
+pub fn plain() {}
+
+#[cfg(any(feature = "net", feature = "tls"))]
+pub fn online() {}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Config;
@@ -0,0 +1,3 @@
+use crate::a::A;
+
+pub fn run(_: A) {}
@@ -0,0 +1,7 @@
+//! A cycle between `a`, `b` and `c`, a module depending on it, and the
+//! usual parent and child referring to each other.
+pub mod a;
+pub mod b;
+pub mod c;
+pub mod d;
+pub mod parent;
@@ -0,0 +1,3 @@
+use super::Error;
+
+pub struct Thing(pub Error);
@@ -0,0 +1,5 @@
+mod child;
+
+pub use child::Thing;
+
+pub struct Error;
@@ -0,0 +1,4 @@
+//! A crate declaring a module whose file is missing.
+pub mod missing;
+
+pub fn connect() {}
@@ -0,0 +1,2 @@
+//! A crate with files no `mod` declaration reaches, next to binaries.
+mod kept;
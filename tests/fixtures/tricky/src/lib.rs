@@ -0,0 +1,22 @@
+//! A crate whose names are awkward to write in every output format.
+pub extern crate core;
+
+pub mod r#match;
+
+pub mod café {
+    pub struct Crème;
+    pub type Error = r#async::Error;
+}
+
+pub struct Café;
+pub fn r#type() {}
+pub fn 名前() {}
+pub trait Alias = Clone + Send;
+pub use self::r#match::*;
+pub const QUOTE: &str = "\"";
+pub static r#static: u8 = 0;
+pub type Ünïcödé = u8;
+pub union Either {
+    left: u8,
+}
+pub(crate) enum r#enum {}
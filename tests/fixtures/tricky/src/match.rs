@@ -0,0 +1,4 @@
+pub struct Ω;
+pub(super) fn r#loop() {}
+pub(in crate::r#match) const _HIDDEN: () = ();
+pub type Json = serde_json::Value;
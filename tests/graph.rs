@@ -0,0 +1,109 @@
+//! Tests for the dependency graph between modules.
+//!
+//! The `graph` fixture has a cycle between `a`, `b` and `c`, a module `d`
+//! depending on `a`, and a `parent` re-exporting from its `child`, which uses
+//! the parent's `Error`.
+use std::path::Path;
+
+use thigpen::graph::{Dependency, ModuleGraph};
+use thigpen::pattern::ModulePattern;
+use thigpen::Lib;
+
+fn fixture() -> Lib {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/graph/src/lib.rs");
+    Lib::from_path("graph", &path)
+}
+
+fn pattern(pattern: &str) -> ModulePattern {
+    ModulePattern::new(pattern).unwrap()
+}
+
+fn steps<'a>(path: &[Dependency<'a>]) -> Vec<(&'a str, &'a str)> {
+    path.iter()
+        .map(|dependency| (dependency.from(), dependency.to()))
+        .collect()
+}
+
+#[test]
+fn cycles() {
+    let lib = fixture();
+    let graph = ModuleGraph::new(&lib);
+    let cycles: Vec<_> = graph
+        .cycles()
+        .iter()
+        .map(|cycle| cycle.modules().to_vec())
+        .collect();
+    assert_eq!(
+        cycles,
+        [
+            vec!["graph::a", "graph::b", "graph::c"],
+            vec!["graph::parent", "graph::parent::child"],
+        ]
+    );
+
+    let cycle = &graph.cycles()[0];
+    let mut dependencies: Vec<_> = steps(cycle.dependencies());
+    dependencies.sort();
+    assert_eq!(
+        dependencies,
+        [
+            ("graph::a", "graph::b"),
+            ("graph::b", "graph::c"),
+            ("graph::c", "graph::a"),
+        ]
+    );
+}
+
+#[test]
+fn cycles_without_ancestors() {
+    let lib = fixture();
+    let graph = ModuleGraph::new(&lib).without_ancestors();
+    let cycles: Vec<_> = graph
+        .cycles()
+        .iter()
+        .map(|cycle| cycle.modules().to_vec())
+        .collect();
+    assert_eq!(cycles, [vec!["graph::a", "graph::b", "graph::c"]]);
+    // The parent still depends on its child.
+    assert!(graph
+        .dependencies()
+        .iter()
+        .any(|dependency| dependency.from() == "graph::parent"
+            && dependency.to() == "graph::parent::child"));
+}
+
+#[test]
+fn shortest_path() {
+    let lib = fixture();
+    let graph = ModuleGraph::new(&lib);
+    let path = graph
+        .shortest_path(&pattern("crate::d"), &pattern("crate::c"))
+        .unwrap();
+    assert_eq!(
+        steps(&path),
+        [
+            ("graph::d", "graph::a"),
+            ("graph::a", "graph::b"),
+            ("graph::b", "graph::c"),
+        ]
+    );
+    assert_eq!(path[0].reference().line(), 1);
+
+    assert!(graph
+        .shortest_path(&pattern("crate::a"), &pattern("crate::d"))
+        .is_none());
+    assert!(graph
+        .shortest_path(&pattern("crate::parent::**"), &pattern("crate::a"))
+        .is_none());
+}
+
+#[test]
+fn dependents() {
+    let lib = fixture();
+    let graph = ModuleGraph::new(&lib);
+    assert_eq!(
+        graph.dependents(&["graph::c"]),
+        ["graph::c", "graph::b", "graph::a", "graph::d"]
+    );
+    assert_eq!(graph.dependents(&["graph::d"]), ["graph::d"]);
+}
@@ -0,0 +1,103 @@
+//! Tests for what a change to some of a crate's files can affect, on the
+//! `graph` fixture, and for finding the changed files with git.
+use std::path::{Path, PathBuf};
+
+use thigpen::impact::Impact;
+use thigpen::Lib;
+
+mod common;
+
+use common::{stdout, TempDir};
+
+fn src() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/graph/src")
+}
+
+fn fixture() -> Lib {
+    Lib::from_path("graph", &src().join("lib.rs"))
+}
+
+#[test]
+fn impact_of_a_module() {
+    let lib = fixture();
+    let impact = Impact::of(&lib, &[src().join("c.rs")]);
+    assert_eq!(impact.changed(), ["graph::c"]);
+    assert_eq!(
+        impact.affected(),
+        ["graph::c", "graph::b", "graph::a", "graph::d"]
+    );
+    let items: Vec<(&str, &str)> = impact
+        .items()
+        .iter()
+        .map(|(module, item)| (*module, item.name()))
+        .collect();
+    assert_eq!(
+        items,
+        [
+            ("graph::c", "C"),
+            ("graph::c", "make"),
+            ("graph::b", "B"),
+            ("graph::a", "A"),
+            ("graph::d", "run"),
+        ]
+    );
+    assert!(impact.unknown().is_empty());
+}
+
+#[test]
+fn impact_of_a_parent_and_its_child() {
+    let lib = fixture();
+    let impact = Impact::of(
+        &lib,
+        &[src().join("parent/child.rs"), src().join("parent/mod.rs")],
+    );
+    assert_eq!(impact.changed(), ["graph::parent::child", "graph::parent"]);
+    assert_eq!(impact.affected(), ["graph::parent::child", "graph::parent"]);
+}
+
+#[test]
+fn impact_of_other_files() {
+    let lib = fixture();
+    let other = src().join("../README.md");
+    let impact = Impact::of(&lib, &[src().join("d.rs"), other]);
+    assert_eq!(impact.changed(), ["graph::d"]);
+    assert_eq!(impact.affected(), ["graph::d"]);
+    assert_eq!(impact.unknown().len(), 1);
+    assert!(impact.unknown()[0].ends_with("README.md"));
+}
+
+#[test]
+fn impact_since_a_revision() {
+    // The crate is in a directory of the repository, not at its top.
+    let repo = TempDir::new("since");
+    repo.manifest("crates/sample", "sample", "0.1.0");
+    repo.write(
+        "crates/sample/src/lib.rs",
+        "pub mod api;\npub mod extra;\npub mod net;\n",
+    );
+    repo.write(
+        "crates/sample/src/api.rs",
+        "pub fn serve() {\n    crate::net::connect()\n}\n",
+    );
+    repo.write("crates/sample/src/net.rs", "pub fn connect() {}\n");
+    repo.commit("v1");
+    // A change to a committed file, a new file, and a file outside the crate.
+    repo.write(
+        "crates/sample/src/net.rs",
+        "pub fn connect() {}\npub fn listen() {}\n",
+    );
+    repo.write("crates/sample/src/extra.rs", "pub struct Extra;\n");
+    repo.write("README.md", "sample\n");
+
+    let output = repo.thigpen_in("crates/sample", &["impact", "--since", "v1"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stderr.is_empty());
+    let stdout = stdout(&output);
+    assert!(
+        stdout.starts_with(
+            "Changed modules:\n  sample::net\n  sample::extra\n\
+             Affected modules:\n  sample::net\n  sample::extra\n  sample::api\n"
+        ),
+        "{stdout}"
+    );
+}
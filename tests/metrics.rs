@@ -0,0 +1,52 @@
+//! Tests for the coupling metrics of each module.
+use thigpen::metrics::ModuleMetrics;
+use thigpen::Lib;
+
+mod common;
+
+use common::TempDir;
+
+#[test]
+fn metrics_of_each_module() {
+    let crate_ = TempDir::new("metrics");
+    crate_.write("src/lib.rs", "pub mod shapes;\npub mod draw;\n");
+    crate_.write(
+        "src/shapes.rs",
+        "\
+pub trait Shape {}
+pub struct Square;
+pub enum Kind { Round }
+pub(crate) trait Internal {}
+trait Private {}
+struct Helper;
+",
+    );
+    crate_.write(
+        "src/draw.rs",
+        "use crate::shapes::Shape;\n\npub fn draw(_: &dyn Shape) {}\n",
+    );
+    let lib = Lib::from_path("sample", &crate_.path().join("src/lib.rs"));
+    let round = |value: f64| (value * 100.0).round() / 100.0;
+    let metrics: Vec<(&str, usize, usize, f64, f64, f64)> = ModuleMetrics::of(&lib)
+        .iter()
+        .map(|metrics| {
+            (
+                metrics.module(),
+                metrics.afferent(),
+                metrics.efferent(),
+                round(metrics.instability()),
+                round(metrics.abstractness()),
+                round(metrics.distance()),
+            )
+        })
+        .collect();
+    assert_eq!(
+        metrics,
+        [
+            ("sample", 0, 0, 0.0, 0.0, 1.0),
+            // Only the `pub` trait counts, against the `pub` types.
+            ("sample::shapes", 1, 0, 0.0, 0.33, 0.67),
+            ("sample::draw", 0, 1, 1.0, 0.0, 0.0),
+        ]
+    );
+}
@@ -0,0 +1,59 @@
+//! Tests for reading back a model written by the JSON renderer.
+//!
+//! The `model` fixture declares a module whose file is missing, so its
+//! model holds a diagnostic.
+use std::path::Path;
+
+use thigpen::render::{Json, Renderer};
+use thigpen::{DiagnosticKind, Lib};
+
+fn fixture() -> Lib {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/model/src/lib.rs");
+    Lib::from_path("model", &path)
+}
+
+fn model(lib: &Lib) -> serde_json::Value {
+    let mut out = vec![];
+    Json.render(lib, &mut out).unwrap();
+    serde_json::from_slice(&out).unwrap()
+}
+
+#[test]
+fn model_keeps_diagnostics() {
+    let lib = fixture();
+    let read = Lib::from_json(&model(&lib).to_string()).unwrap();
+
+    let diagnostics = read.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind(), DiagnosticKind::MissingFile);
+    assert_eq!(diagnostics[0].usepath(), "model::missing");
+    let [mod_rs, rs] = diagnostics[0].candidates();
+    assert!(mod_rs.ends_with("src/missing/mod.rs"));
+    assert!(rs.ends_with("src/missing.rs"));
+}
+
+#[test]
+fn model_needs_both_candidates() {
+    let mut model = model(&fixture());
+    let candidates = model["modules"][0]["diagnostic"]["candidates"]
+        .as_array_mut()
+        .unwrap();
+    candidates.pop();
+    assert!(Lib::from_json(&model.to_string()).is_err());
+}
+
+#[test]
+fn orphans_refuses_a_model() {
+    let path = std::env::temp_dir().join(format!("thigpen-model-{}.json", std::process::id()));
+    std::fs::write(&path, model(&fixture()).to_string()).unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_thigpen"))
+        .arg("--model")
+        .arg(&path)
+        .arg("orphans")
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+}
@@ -0,0 +1,15 @@
+//! Tests for finding the files no `mod` declaration reaches, on the
+//! `orphans` fixture.
+use std::path::Path;
+
+use thigpen::Lib;
+
+#[test]
+fn orphans_leave_out_binaries() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/orphans/src");
+    let lib = Lib::from_path("orphans", &src.join("lib.rs"));
+    assert_eq!(
+        lib.orphans(),
+        [src.join("old/gone.rs"), src.join("stale.rs")]
+    );
+}
@@ -0,0 +1,35 @@
+//! Tests for the options of drawing the crate, run on this crate.
+use std::process::{Command, Output};
+
+fn thigpen(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_thigpen"))
+        .arg(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn split_needs_mermaid() {
+    let output = thigpen(&["--output-type", "dot", "--split", "-o", "unused"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "--split only works with --output-type mermaid\n"
+    );
+}
+
+#[test]
+fn scale_needs_dot() {
+    let output = thigpen(&["--output-type", "tree", "--scale"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "--scale only works with --output-type dot\n"
+    );
+
+    let output = thigpen(&["--output-type", "dot", "--scale"]);
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -0,0 +1,532 @@
+//! Conformance tests for the built-in renderers.
+//!
+//! The `tricky` fixture is full of names that are awkward in one format or
+//! another: raw identifiers, keywords, unicode identifiers, item kinds with
+//! spaces in them, a crate name with quotes and separators, and external
+//! crates used from modules with awkward names. Every renderer must produce
+//! well-formed output for it, from which the original names can be read
+//! back.
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use thigpen::graph::ModuleGraph;
+use thigpen::render::{Dot, Json, Mermaid, Renderer, Renderers, Tree};
+use thigpen::{Lib, Module, PublicIdentifier};
+
+const CRATE_NAME: &str = "tri\"ck,y\\";
+
+fn fixture() -> Lib {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tricky/src/lib.rs");
+    Lib::from_path(CRATE_NAME, &path)
+        .with_external_crates(vec!["r#async".into(), "serde_json".into()])
+}
+
+fn render(renderer: &dyn Renderer, lib: &Lib) -> String {
+    let mut out = vec![];
+    renderer.render(lib, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+/// The crate and every module in it, with their public identifiers.
+fn entities(lib: &Lib) -> Vec<(String, &[PublicIdentifier])> {
+    fn walk<'a>(module: &'a Module, entities: &mut Vec<(String, &'a [PublicIdentifier])>) {
+        entities.push((module.usepath().into(), module.interface()));
+        module
+            .children()
+            .iter()
+            .for_each(|child| walk(child, entities));
+    }
+    let mut entities = vec![(lib.name().to_string(), lib.interface())];
+    lib.modules()
+        .iter()
+        .for_each(|module| walk(module, &mut entities));
+    entities
+}
+
+/// Every (parent, child) pair of modules.
+fn edges(lib: &Lib) -> BTreeSet<(String, String)> {
+    fn walk(parent: &str, module: &Module, edges: &mut BTreeSet<(String, String)>) {
+        edges.insert((parent.into(), module.usepath().into()));
+        module
+            .children()
+            .iter()
+            .for_each(|child| walk(module.usepath(), child, edges));
+    }
+    let mut edges = BTreeSet::new();
+    lib.modules()
+        .iter()
+        .for_each(|module| walk(lib.name(), module, &mut edges));
+    edges
+}
+
+/// Every (module, external crate) pair of a module using an external crate.
+fn externals(lib: &Lib) -> BTreeSet<(String, String)> {
+    ModuleGraph::new(lib)
+        .external_dependencies()
+        .iter()
+        .map(|dependency| (dependency.from().into(), dependency.to().into()))
+        .collect()
+}
+
+#[test]
+fn fixture_is_tricky() {
+    let lib = fixture();
+    let names: BTreeSet<String> = entities(&lib)
+        .iter()
+        .flat_map(|(_, interface)| interface.iter().map(|item| item.name().to_string()))
+        .collect();
+    for name in ["r#type", "r#static", "名前", "Ünïcödé", "Ω", "*", "core"] {
+        assert!(names.contains(name), "{name} missing from {names:?}");
+    }
+    let kinds: BTreeSet<String> = entities(&lib)
+        .iter()
+        .flat_map(|(_, interface)| interface.iter().map(|item| item.kind().to_string()))
+        .collect();
+    assert!(kinds.contains("extern crate"));
+    assert!(kinds.contains("trait alias"));
+    let crates: BTreeSet<String> = externals(&lib).into_iter().map(|(_, name)| name).collect();
+    assert_eq!(
+        crates,
+        BTreeSet::from(["r#async".into(), "serde_json".into()])
+    );
+}
+
+#[test]
+fn every_renderer_has_a_conformance_check() {
+    let checked = ["mermaid", "dot", "tree", "csv", "tsv", "json"];
+    for name in Renderers::default().names() {
+        assert!(
+            checked.contains(&name.as_str()),
+            "renderer {name} has no conformance check"
+        );
+    }
+}
+
+/// Read a mermaid string from the start of `input`.
+fn mermaid_string(input: &str) -> (String, &str) {
+    let input = input.strip_prefix('"').expect("string must start with \"");
+    let end = input.find('"').expect("string must end with \"");
+    let text = &input[..end];
+    assert!(!text.contains(['\n', '\r']), "line break in {text:?}");
+    (text.replace("#quot;", "\""), &input[end + 1..])
+}
+
+/// Read a bare mermaid word from the start of `input`.
+fn mermaid_word(input: &str) -> (&str, &str) {
+    let end = input.find(' ').unwrap_or(input.len());
+    let word = &input[..end];
+    assert!(
+        word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_'),
+        "invalid word {word:?}"
+    );
+    assert!(
+        word.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+        "invalid word {word:?}"
+    );
+    (word, &input[end..])
+}
+
+/// An entity of a mermaid diagram, with the kind and name of each attribute.
+type Entity = (String, Vec<(String, String)>);
+
+/// A mermaid entity diagram: the relationships between modules, the
+/// relationships from modules to external crates, and the entities.
+type Diagram = (
+    BTreeSet<(String, String)>,
+    BTreeSet<(String, String)>,
+    Vec<Entity>,
+);
+
+/// Parse a mermaid entity diagram.
+fn parse_mermaid(output: &str) -> Diagram {
+    let mut lines = output.lines();
+    assert_eq!(lines.next(), Some("---"));
+    assert!(lines.next().unwrap().starts_with("title: "));
+    assert_eq!(lines.next(), Some("---"));
+    assert_eq!(lines.next(), Some("erDiagram"));
+
+    let mut relationships = BTreeSet::new();
+    let mut externals = BTreeSet::new();
+    let mut entities: Vec<Entity> = vec![];
+    let mut entity: Option<Entity> = None;
+    for line in lines {
+        if let Some(rest) = line.strip_prefix("    ") {
+            let (kind, rest) = mermaid_word(rest);
+            let (word, rest) = mermaid_word(rest.strip_prefix(' ').unwrap());
+            let name = match rest.strip_prefix(' ') {
+                Some(comment) => {
+                    let (name, rest) = mermaid_string(comment);
+                    assert_eq!(rest, "");
+                    name
+                }
+                None => {
+                    assert_eq!(rest, "");
+                    word.into()
+                }
+            };
+            entity.as_mut().unwrap().1.push((kind.into(), name));
+        } else if line == "  }" {
+            entities.push(entity.take().unwrap());
+        } else {
+            let rest = line.strip_prefix("  ").unwrap();
+            let (from, rest) = mermaid_string(rest);
+            if rest == " {" {
+                assert!(entity.is_none());
+                entity = Some((from, vec![]));
+            } else if let Some(rest) = rest.strip_prefix(" }o..o{ ") {
+                let (to, rest) = mermaid_string(rest);
+                assert_eq!(rest, " : \"uses\"");
+                let name = to.strip_prefix("extern crate ").unwrap();
+                externals.insert((from, name.into()));
+            } else {
+                let rest = rest.strip_prefix(" ||--|{ ").unwrap();
+                let (to, rest) = mermaid_string(rest);
+                assert_eq!(rest, " : \"\"");
+                relationships.insert((from, to));
+            }
+        }
+    }
+    assert!(entity.is_none());
+    (relationships, externals, entities)
+}
+
+/// The entities every mermaid diagram of `lib` should hold, sorted.
+fn mermaid_entities(lib: &Lib) -> Vec<Entity> {
+    let mut expected: Vec<Entity> = entities(lib)
+        .into_iter()
+        .map(|(name, interface)| {
+            let items = interface
+                .iter()
+                .map(|item| {
+                    (
+                        item.kind().to_string().replace(' ', "_"),
+                        item.name().to_string(),
+                    )
+                })
+                .collect();
+            (name, items)
+        })
+        .collect();
+    expected.sort();
+    expected
+}
+
+#[test]
+fn mermaid() {
+    let lib = fixture();
+    let output = render(Renderers::default().get("mermaid").unwrap(), &lib);
+    let (relationships, uses, mut entities) = parse_mermaid(&output);
+
+    assert_eq!(relationships, edges(&lib));
+    assert_eq!(uses, externals(&lib));
+    entities.sort();
+    assert_eq!(entities, mermaid_entities(&lib));
+}
+
+#[test]
+fn mermaid_pages() {
+    let lib = fixture();
+    let directory = std::env::temp_dir().join(format!("thigpen-pages-{}", std::process::id()));
+    Mermaid::split(Some(0))
+        .render_to_path(&lib, &directory)
+        .unwrap();
+
+    let index = std::fs::read_to_string(directory.join("index.mmd")).unwrap();
+    let mut relationships = BTreeSet::new();
+    let mut uses = BTreeSet::new();
+    let mut entities = vec![];
+    for line in index.lines() {
+        if let Some(rest) = line.strip_prefix("  click ") {
+            let (_, filename) = rest.split_once(' ').unwrap();
+            let (filename, rest) = mermaid_string(filename);
+            assert_eq!(rest, "");
+            let page = std::fs::read_to_string(directory.join(filename)).unwrap();
+            let (page_relationships, page_uses, page_entities) = parse_mermaid(&page);
+            relationships.extend(page_relationships);
+            uses.extend(page_uses);
+            entities.extend(page_entities);
+        }
+    }
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(relationships, edges(&lib));
+    assert_eq!(uses, externals(&lib));
+    entities.sort();
+    assert_eq!(entities, mermaid_entities(&lib));
+}
+
+/// Read a quoted graphviz ID from the start of `input`.
+fn dot_id(input: &str) -> (String, &str) {
+    let mut chars = input
+        .strip_prefix('"')
+        .expect("ID must be quoted")
+        .char_indices();
+    let mut id = String::new();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return (id, &input[index + 2..]),
+            '\\' => match chars.next().unwrap().1 {
+                'n' => id.push('\n'),
+                'r' => id.push('\r'),
+                escaped => id.push(escaped),
+            },
+            '\n' | '\r' => panic!("line break in ID"),
+            c => id.push(c),
+        }
+    }
+    panic!("ID must end with \"");
+}
+
+/// A graphviz graph of a crate: the nodes given a font size, the dashed
+/// nodes of external crates, the edges between modules and the dashed edges
+/// from modules to external crates.
+#[derive(Default)]
+struct Graph {
+    sized: Vec<String>,
+    crates: BTreeSet<String>,
+    edges: BTreeSet<(String, String)>,
+    uses: BTreeSet<(String, String)>,
+}
+
+/// Parse a graphviz graph as written by the dot renderer.
+fn parse_dot(output: &str) -> Graph {
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.first(), Some(&"graph graphname {"));
+    assert_eq!(lines.last(), Some(&"}"));
+    let mut graph = Graph::default();
+    for line in lines[1..lines.len() - 1].iter() {
+        let (from, rest) = dot_id(line);
+        if let Some(rest) = rest.strip_prefix(" -- ") {
+            let (to, rest) = dot_id(rest);
+            match rest {
+                "" => graph.edges.insert((from, to)),
+                " [style=dashed]" => graph.uses.insert((from, to)),
+                _ => panic!("unexpected edge attributes {rest:?}"),
+            };
+        } else if rest == " [shape=box, style=dashed]" {
+            graph.crates.insert(from);
+        } else {
+            let fontsize = rest
+                .strip_prefix(" [fontsize=")
+                .and_then(|rest| rest.strip_suffix(']'))
+                .expect("node must only set fontsize");
+            assert!(fontsize.parse::<u32>().is_ok(), "{fontsize}");
+            graph.sized.push(from);
+        }
+    }
+    graph
+}
+
+/// Check the edges and external crates of a graph of `lib`.
+fn check_dot(lib: &Lib, graph: &Graph) {
+    assert_eq!(graph.edges, edges(lib));
+    let uses = externals(lib);
+    assert_eq!(graph.uses, uses);
+    let crates: BTreeSet<String> = uses.into_iter().map(|(_, name)| name).collect();
+    assert_eq!(graph.crates, crates);
+}
+
+#[test]
+fn dot() {
+    let lib = fixture();
+    let output = render(Renderers::default().get("dot").unwrap(), &lib);
+    let graph = parse_dot(&output);
+    assert!(graph.sized.is_empty());
+    check_dot(&lib, &graph);
+}
+
+#[test]
+fn dot_scaled() {
+    let lib = fixture();
+    let output = render(&Dot::scaled(), &lib);
+    let mut graph = parse_dot(&output);
+    let mut expected: Vec<String> = entities(&lib).into_iter().map(|(name, _)| name).collect();
+    graph.sized.sort();
+    expected.sort();
+    assert_eq!(graph.sized, expected);
+    check_dot(&lib, &graph);
+}
+
+/// Parse RFC 4180 CSV into records. A blank line is an empty record.
+fn parse_csv(input: &str) -> Vec<Vec<String>> {
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') => {
+                assert!(field.is_empty(), "quote inside unquoted field");
+                quoted = true;
+            }
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\n') => {
+                if !(record.is_empty() && field.is_empty()) {
+                    record.push(std::mem::take(&mut field));
+                }
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    assert!(!quoted, "unterminated quoted field");
+    assert!(
+        record.is_empty() && field.is_empty(),
+        "missing final newline"
+    );
+    records
+}
+
+/// Check the items and edges tables of the csv or tsv renderers.
+fn check_tables(lib: &Lib, records: Vec<Vec<String>>) {
+    let mut tables = records.split(|record| record.is_empty());
+    let items = tables.next().unwrap();
+    let edge_rows = tables.next().unwrap();
+    assert!(tables.next().is_none());
+
+    assert_eq!(
+        items[0],
+        [
+            "crate",
+            "module",
+            "kind",
+            "name",
+            "visibility",
+            "file",
+            "line"
+        ]
+    );
+    let mut read: Vec<Vec<String>> = items[1..]
+        .iter()
+        .map(|record| {
+            assert_eq!(record.len(), 7, "{record:?}");
+            assert_eq!(record[0], CRATE_NAME);
+            assert!(record[5].starts_with("src/"), "{record:?}");
+            record[1..5].to_vec()
+        })
+        .collect();
+    let mut expected: Vec<Vec<String>> = entities(lib)
+        .into_iter()
+        .flat_map(|(module, interface)| {
+            interface.iter().map(move |item| {
+                vec![
+                    module.clone(),
+                    item.kind().to_string(),
+                    item.name().to_string(),
+                    item.visibility().to_string(),
+                ]
+            })
+        })
+        .collect();
+    read.sort();
+    expected.sort();
+    assert_eq!(read, expected);
+
+    assert_eq!(edge_rows[0], ["from", "to", "kind"]);
+    let mut relationships = BTreeSet::new();
+    let mut uses = BTreeSet::new();
+    for record in edge_rows[1..].iter() {
+        assert_eq!(record.len(), 3, "{record:?}");
+        let edge = (record[0].clone(), record[1].clone());
+        match record[2].as_str() {
+            "mod" => relationships.insert(edge),
+            "extern" => uses.insert(edge),
+            kind => panic!("unexpected edge kind {kind:?}"),
+        };
+    }
+    assert_eq!(relationships, edges(lib));
+    assert_eq!(uses, externals(lib));
+}
+
+#[test]
+fn csv() {
+    let lib = fixture();
+    let output = render(Renderers::default().get("csv").unwrap(), &lib);
+    check_tables(&lib, parse_csv(&output));
+}
+
+#[test]
+fn tsv() {
+    let lib = fixture();
+    let output = render(Renderers::default().get("tsv").unwrap(), &lib);
+    let records = output
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                vec![]
+            } else {
+                line.split('\t').map(String::from).collect()
+            }
+        })
+        .collect();
+    check_tables(&lib, records);
+}
+
+/// Remove ANSI SGR sequences from `input`.
+fn strip_ansi(input: &str) -> String {
+    let mut output = String::new();
+    let mut rest = input;
+    while let Some(start) = rest.find("\x1b[") {
+        output.push_str(&rest[..start]);
+        let end = rest[start..].find('m').unwrap();
+        rest = &rest[start + end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+#[test]
+fn tree() {
+    let lib = fixture();
+    let output = render(Renderers::default().get("tree").unwrap(), &lib);
+    assert!(!output.contains(|c: char| c.is_control() && c != '\n'));
+    assert_eq!(output.lines().next(), Some(CRATE_NAME));
+    for (_, interface) in entities(&lib) {
+        for item in interface {
+            let line = format!("{} {} {}", item.visibility(), item.kind(), item.name());
+            assert!(
+                output.lines().any(|l| l.ends_with(&line)),
+                "{line:?} missing from tree"
+            );
+        }
+    }
+
+    let colored = render(&Tree::new(None, true), &lib);
+    assert_ne!(colored, output);
+    assert_eq!(strip_ansi(&colored), output);
+}
+
+#[test]
+fn json() {
+    let lib = fixture();
+    let output = render(Renderers::default().get("json").unwrap(), &lib);
+    let read = Lib::from_json(&output).unwrap();
+
+    assert_eq!(read.name(), CRATE_NAME);
+    assert_eq!(edges(&read), edges(&lib));
+    assert_eq!(mermaid_entities(&read), mermaid_entities(&lib));
+    // Everything else survives too, so the same output comes out again.
+    assert_eq!(render(&Json, &read), output);
+}
+
+#[test]
+fn renderers_new_holds_the_built_ins() {
+    assert_eq!(Renderers::new().names(), Renderers::default().names());
+    assert!(Renderers::new().get("mermaid").is_some());
+    assert!(Renderers::empty().names().is_empty());
+}
+
+#[test]
+#[allow(deprecated)]
+fn create_mermaid_matches_the_mermaid_renderer() {
+    let lib = fixture();
+    assert_eq!(lib.create_mermaid(), render(&Mermaid::default(), &lib));
+}
@@ -0,0 +1,89 @@
+//! Tests for reading a crate from a revision of its git repository with
+//! `--rev` or sampling it with `history`, run against a repository made for
+//! each test.
+mod common;
+
+use common::{stderr, stdout, TempDir};
+
+/// A repository for the crate `sample`, with nothing committed yet.
+fn repo(name: &str) -> TempDir {
+    let repo = TempDir::new(name);
+    repo.manifest(".", "sample", "0.1.0");
+    repo
+}
+
+#[test]
+fn rev_reads_the_committed_files() {
+    let repo = repo("rev-committed");
+    repo.write("src/lib.rs", "pub mod net;\n");
+    repo.write("src/net.rs", "pub fn connect() {}\n");
+    repo.commit("v1");
+    // Neither change is in v1.
+    repo.write("src/net.rs", "pub fn connect() {}\npub fn listen() {}\n");
+    repo.write("src/extra.rs", "pub struct Unused;\n");
+
+    let output = repo.thigpen(&["--rev", "v1", "api"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "sample::net: mod net\nsample::net::connect: fn connect()\n"
+    );
+
+    let output = repo.thigpen(&["--rev", "v1", "orphans"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+
+    let output = repo.thigpen(&["api"]);
+    assert!(stdout(&output).contains("sample::net::listen: fn listen()"));
+}
+
+#[test]
+fn rev_reports_unparsable_files() {
+    let repo = repo("rev-unparsable");
+    repo.write("src/lib.rs", "pub mod net;\n");
+    repo.write("src/net.rs", "pub fn connect( {}\n");
+    repo.commit("broken");
+
+    let output = repo.thigpen(&["--rev", "broken", "cycles"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = stderr(&output);
+    assert!(!stderr.contains("panicked"), "{stderr}");
+    assert!(
+        stderr.starts_with(&format!(
+            "Could not parse {}",
+            repo.path().join("src/net.rs").display()
+        )),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn rev_reports_unknown_revisions() {
+    let repo = repo("rev-unknown");
+    repo.write("src/lib.rs", "pub fn connect() {}\n");
+    repo.commit("v1");
+
+    let output = repo.thigpen(&["--rev", "v2", "api"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("v2 is not a revision"));
+}
+
+#[test]
+fn history_skips_unparsable_revisions() {
+    let repo = repo("history-unparsable");
+    repo.write("src/lib.rs", "pub fn connect() {}\n");
+    repo.commit("v1");
+    repo.write("src/lib.rs", "pub fn connect( {}\n");
+    repo.commit("v2");
+    repo.write("src/lib.rs", "pub fn connect() {}\npub fn listen() {}\n");
+    repo.commit("v3");
+
+    let output = repo.thigpen(&["history"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(stderr(&output).starts_with("warning: skipping v2: Could not parse"));
+    let revisions: Vec<String> = stdout(&output)
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').next().unwrap().to_string())
+        .collect();
+    assert_eq!(revisions, ["v1", "v3"]);
+}
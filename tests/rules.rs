@@ -0,0 +1,119 @@
+//! Tests for module patterns and the rules built from them, checked against
+//! the `graph` fixture.
+use std::path::Path;
+
+use thigpen::graph::ModuleGraph;
+use thigpen::pattern::ModulePattern;
+use thigpen::rules::Rules;
+use thigpen::Lib;
+
+fn fixture() -> Lib {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/graph/src/lib.rs");
+    Lib::from_path("graph", &path)
+}
+
+fn matches(pattern: &str, usepath: &str) -> bool {
+    ModulePattern::new(pattern)
+        .unwrap()
+        .matches(usepath, "graph")
+}
+
+#[test]
+fn pattern_globs() {
+    assert!(matches("crate::a", "graph::a"));
+    assert!(matches("graph::a", "graph::a"));
+    assert!(!matches("crate::a", "graph::a::inner"));
+
+    assert!(matches("crate::*", "graph::a"));
+    assert!(!matches("crate::*", "graph"));
+    assert!(!matches("crate::*", "graph::parent::child"));
+
+    assert!(matches("crate::**", "graph"));
+    assert!(matches("crate::**", "graph::parent::child"));
+    assert!(matches("crate::parent::**", "graph::parent"));
+    assert!(matches("crate::**::child", "graph::parent::child"));
+    assert!(!matches("crate::**::child", "graph::parent"));
+
+    assert!(matches("crate::par*", "graph::parent"));
+    assert!(matches("crate::*ent::ch*d", "graph::parent::child"));
+    assert!(!matches("crate::par*", "graph::a"));
+
+    // Only a leading `crate` stands for the crate.
+    assert!(!matches("graph::crate", "graph::graph"));
+}
+
+#[test]
+fn pattern_errors() {
+    for invalid in ["", "crate::", "crate::::a", "::a"] {
+        assert!(ModulePattern::new(invalid).is_err(), "{invalid:?}");
+    }
+    assert_eq!(
+        ModulePattern::new("crate::**").unwrap().to_string(),
+        "crate::**"
+    );
+}
+
+#[test]
+fn rules_check() {
+    let rules = Rules::from_toml(
+        r#"
+        [[rule]]
+        from = "crate::d"
+        forbid = ["crate::a"]
+
+        [[rule]]
+        from = "crate::parent::**"
+        allow = ["crate::parent::**"]
+
+        [[rule]]
+        from = "crate::*"
+        allow = ["crate::a", "crate::b", "crate::parent::**"]
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        rules.rules()[0].to_string(),
+        "crate::d must not depend on crate::a"
+    );
+    assert_eq!(
+        rules.rules()[2].to_string(),
+        "crate::* may only depend on crate::a, crate::b, crate::parent::**"
+    );
+
+    let lib = fixture();
+    let graph = ModuleGraph::new(&lib);
+    let violations: Vec<_> = rules
+        .check(&graph)
+        .iter()
+        .map(|violation| {
+            let dependency = violation.dependency();
+            (
+                violation.rule().to_string(),
+                dependency.from(),
+                dependency.to(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        violations,
+        [
+            (
+                "crate::* may only depend on crate::a, crate::b, crate::parent::**".into(),
+                "graph::b",
+                "graph::c",
+            ),
+            (
+                "crate::d must not depend on crate::a".into(),
+                "graph::d",
+                "graph::a",
+            ),
+        ]
+    );
+}
+
+#[test]
+fn rules_errors() {
+    assert!(Rules::from_toml("[[rule]]\nfrom = \"crate::a\"\ndeny = []\n").is_err());
+    assert!(Rules::from_toml("[[rule]]\nfrom = \"crate::\"\n").is_err());
+    assert!(Rules::from_toml("").unwrap().rules().is_empty());
+}
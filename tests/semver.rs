@@ -0,0 +1,195 @@
+//! Tests for classifying API changes and bumping version numbers.
+use std::path::PathBuf;
+use std::process::Command;
+
+use thigpen::api::Api;
+use thigpen::semver::{self, Change, Level, Version};
+use thigpen::Lib;
+
+mod common;
+
+use common::TempDir;
+
+const OLD: &str = "\
+pub fn removed() {}
+pub fn changed(_: u8) {}
+pub enum Open { A }
+#[non_exhaustive]
+pub enum Closed { A }
+pub struct Public { pub a: u8 }
+pub struct Private { pub a: u8, b: u8 }
+pub trait Trait { fn required(&self); }
+pub fn same() {}
+";
+
+const NEW: &str = "\
+pub fn added() {}
+pub fn changed(_: u16) {}
+pub enum Open { A, B }
+#[non_exhaustive]
+pub enum Closed { A, B }
+pub struct Public { pub a: u8, pub b: u8 }
+pub struct Private { pub a: u8, b: u8, pub c: u8 }
+pub trait Trait { fn required(&self); fn provided(&self) {} fn also_required(&self); }
+pub fn same() {}
+";
+
+/// Write a crate named `sample` at `version` to `name` in `dir`, returning
+/// its directory.
+fn write_crate(dir: &TempDir, name: &str, version: &str, lib_rs: &str) -> PathBuf {
+    dir.manifest(name, "sample", version);
+    dir.write(&format!("{name}/src/lib.rs"), lib_rs);
+    dir.path().join(name)
+}
+
+fn version(version: &str) -> Version {
+    version.parse().unwrap()
+}
+
+#[test]
+fn changes_are_classified() {
+    let crates = TempDir::new("semver-classified");
+    let old = Lib::from_path(
+        "sample",
+        &write_crate(&crates, "old", "1.0.0", OLD).join("src/lib.rs"),
+    );
+    let new = Lib::from_path(
+        "sample",
+        &write_crate(&crates, "new", "1.0.0", NEW).join("src/lib.rs"),
+    );
+    let (old, new) = (Api::of(&old), Api::of(&new));
+
+    let changes = Change::between(&old, &new);
+    let classified: Vec<(Level, &str)> = changes
+        .iter()
+        .map(|change| (change.level(), change.path()))
+        .collect();
+    assert_eq!(
+        classified,
+        [
+            (Level::Minor, "sample::Closed::B"),
+            (Level::Major, "sample::Open::B"),
+            (Level::Minor, "sample::Private::c"),
+            (Level::Major, "sample::Public::b"),
+            (Level::Major, "sample::Trait::also_required"),
+            (Level::Minor, "sample::Trait::provided"),
+            (Level::Minor, "sample::added"),
+            (Level::Major, "sample::changed"),
+            (Level::Major, "sample::removed"),
+        ]
+    );
+    let changed = &changes[7];
+    assert_eq!(changed.before().unwrap().signature(), "fn changed(_: u8)");
+    assert_eq!(changed.after().unwrap().signature(), "fn changed(_: u16)");
+    assert_eq!(semver::level(&changes), Level::Major);
+
+    assert!(Change::between(&old, &old).is_empty());
+    assert_eq!(semver::level(&[]), Level::Patch);
+}
+
+#[test]
+fn semver_checks_the_declared_version() {
+    let crates = TempDir::new("semver-declared");
+    let old = write_crate(&crates, "old", "1.0.0", OLD);
+    let status = |version: &str, lib_rs: &str| {
+        let new = write_crate(&crates, version, version, lib_rs);
+        Command::new(env!("CARGO_BIN_EXE_thigpen"))
+            .arg(&new)
+            .arg("semver")
+            .arg(&old)
+            .output()
+            .unwrap()
+            .status
+            .code()
+    };
+    assert_eq!(status("1.0.0", NEW), Some(1));
+    assert_eq!(status("1.1.0", NEW), Some(1));
+    assert_eq!(status("2.0.0-rc.1", NEW), Some(1));
+    assert_eq!(status("2.0.0", NEW), Some(0));
+    // Even a release that changes nothing needs a new version.
+    assert_eq!(status("1.0.0", OLD), Some(1));
+    assert_eq!(status("1.0.1", OLD), Some(0));
+}
+
+#[test]
+fn version_round_trips() {
+    for written in [
+        "0.2.1",
+        "1.0.0-beta.1",
+        "1.2.3+build.5",
+        "1.0.0-rc-1+x86-64",
+        "0.0.0-0.a0",
+        "1.0.0+build.01",
+    ] {
+        assert_eq!(version(written).to_string(), written);
+    }
+    let parsed = version("1.0.0-beta.1+exp.sha.5114f85");
+    assert_eq!((parsed.major, parsed.minor, parsed.patch), (1, 0, 0));
+    assert_eq!(parsed.pre, "beta.1");
+    assert_eq!(parsed.build, "exp.sha.5114f85");
+
+    for invalid in [
+        "1.2",
+        "1.2.3.4",
+        "1.2.x",
+        "1.2.3-",
+        "1.2.3+",
+        "1.2.3-beta..1",
+        "1.2.3-b_1",
+        "01.2.3",
+        "1.02.3",
+        "1.2.03",
+        "1.2.3-beta.01",
+        "+1.2.3",
+    ] {
+        assert!(invalid.parse::<Version>().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn version_precedence() {
+    // The example from the SemVer specification.
+    let ordered = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc.1",
+        "1.0.0",
+        "1.0.1",
+        "1.1.0",
+        "2.0.0",
+    ];
+    for pair in ordered.windows(2) {
+        assert!(version(pair[0]) < version(pair[1]), "{pair:?}");
+    }
+    assert_ne!(version("1.0.0+a"), version("1.0.0+b"));
+}
+
+#[test]
+fn version_bump() {
+    let bump = |from: &str, level| version(from).bump(level).to_string();
+
+    assert_eq!(bump("1.2.3", Level::Major), "2.0.0");
+    assert_eq!(bump("1.2.3", Level::Minor), "1.3.0");
+    assert_eq!(bump("1.2.3", Level::Patch), "1.2.4");
+    assert_eq!(bump("1.2.3+build", Level::Patch), "1.2.4");
+
+    // Before 1.0.0 everything shifts down one place.
+    assert_eq!(bump("0.2.1", Level::Major), "0.3.0");
+    assert_eq!(bump("0.2.1", Level::Minor), "0.2.2");
+    assert_eq!(bump("0.2.1", Level::Patch), "0.2.2");
+    assert_eq!(bump("0.0.3", Level::Major), "0.0.4");
+
+    // A pre-release is followed by its release when that is enough.
+    assert_eq!(bump("1.0.0-beta.1", Level::Major), "1.0.0");
+    assert_eq!(bump("1.1.0-rc.1", Level::Major), "2.0.0");
+    assert_eq!(bump("1.1.0-rc.1", Level::Minor), "1.1.0");
+    assert_eq!(bump("1.1.1-rc.1", Level::Minor), "1.2.0");
+    assert_eq!(bump("1.1.1-rc.1", Level::Patch), "1.1.1");
+    assert_eq!(bump("0.3.0-alpha", Level::Major), "0.3.0");
+    assert_eq!(bump("0.3.1-alpha", Level::Major), "0.4.0");
+    assert_eq!(bump("0.3.1-alpha", Level::Minor), "0.3.1");
+}
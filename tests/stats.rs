@@ -0,0 +1,99 @@
+//! Tests for counting the lines, items and complexity of a module.
+use thigpen::Lib;
+
+mod common;
+
+use common::TempDir;
+
+fn lib(name: &str, lib_rs: &str) -> (TempDir, Lib) {
+    let crate_ = TempDir::new(name);
+    crate_.write("src/lib.rs", lib_rs);
+    let lib = Lib::from_path("sample", &crate_.path().join("src/lib.rs"));
+    (crate_, lib)
+}
+
+#[test]
+fn lines_by_what_they_hold() {
+    let (_crate, lib) = lib(
+        "stats-lines",
+        r#"//! A line comment.
+
+pub fn f() {} /* A block comment
+   after code, still going
+*/ pub fn g() {}
+/* /* Nested */ still a comment */
+pub const URL: &str = "http://example.com/*";
+pub fn h() {}
+"#,
+    );
+    let lines = lib.stats().lines();
+    assert_eq!(
+        (lines.total, lines.code, lines.comment, lines.blank),
+        (8, 4, 3, 1)
+    );
+}
+
+#[test]
+fn items_of_inline_modules_count() {
+    let (_crate, lib) = lib(
+        "stats-items",
+        "\
+pub struct S;
+mod inline {
+    pub fn f() {}
+    impl super::S {
+        pub fn g(&self) {}
+    }
+}
+",
+    );
+    let stats = lib.stats();
+    let items: Vec<(&str, usize)> = stats
+        .items()
+        .iter()
+        .map(|(kind, count)| (kind.as_str(), *count))
+        .collect();
+    assert_eq!(items, [("fn", 1), ("impl", 1), ("mod", 1), ("struct", 1)]);
+    assert_eq!(stats.functions(), 2);
+}
+
+#[test]
+fn complexity_counts_branches() {
+    let (_crate, lib) = lib(
+        "stats-complexity",
+        "\
+fn straight() {}
+
+fn branches(a: bool, b: Option<u8>) -> Option<u8> {
+    if a && b.is_some() {
+        for _ in 0..2 {}
+    }
+    while a {}
+    let c = b?;
+    match c {
+        0 => None,
+        1 if a => None,
+        _ => {
+            fn nested() -> bool {
+                true || false
+            }
+            Some(c)
+        }
+    }
+}
+
+trait T {
+    fn provided(&self) {
+        if true {}
+    }
+    fn required(&self);
+}
+",
+    );
+    let stats = lib.stats();
+    // `branches`: 1, `if`, `&&`, `for`, `while`, `?`, two more arms and a
+    // guard. `nested` is counted on its own.
+    assert_eq!(stats.complexities(), [1, 9, 2, 2]);
+    assert_eq!(stats.max_complexity(), 9);
+    assert_eq!(stats.average_complexity(), 3.5);
+}
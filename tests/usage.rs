@@ -0,0 +1,106 @@
+//! Tests for finding where each item is used from, through glob imports,
+//! aliases and the crate's tests.
+use thigpen::usage::Usage;
+use thigpen::{Lib, Reference};
+
+mod common;
+
+use common::{stdout, TempDir};
+
+/// A crate whose module `b` glob imports `a`, `c` uses `b` under another
+/// name, and only the tests use `d::tested`.
+fn sample(name: &str) -> TempDir {
+    let crate_ = TempDir::new(name);
+    crate_.manifest(".", "sample", "0.1.0");
+    crate_.write(
+        "src/lib.rs",
+        "pub mod a;\npub mod b;\npub mod c;\npub mod d;\n",
+    );
+    crate_.write("src/a.rs", "pub fn first() {}\npub fn second() {}\n");
+    crate_.write(
+        "src/b.rs",
+        "use crate::a::*;\n\npub fn aliased() {\n    first();\n}\n",
+    );
+    crate_.write(
+        "src/c.rs",
+        "use crate::b as other;\n\npub fn run() {\n    other::aliased();\n}\n",
+    );
+    crate_.write("src/d.rs", "pub fn tested() {}\npub fn unused() {}\n");
+    crate_.write(
+        "tests/d.rs",
+        "#[test]\nfn tested() {\n    sample::d::tested();\n}\n",
+    );
+    crate_
+}
+
+/// The modules using each item, by the item's path.
+fn users(usages: &[Usage]) -> Vec<(String, Vec<String>, usize)> {
+    usages
+        .iter()
+        .map(|usage| {
+            let mut modules: Vec<String> = usage
+                .modules()
+                .iter()
+                .map(|module| module.to_string())
+                .collect();
+            modules.sort();
+            (
+                format!("{}::{}", usage.module(), usage.item().name()),
+                modules,
+                usage.external().len(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn usage_through_globs_aliases_and_tests() {
+    let crate_ = sample("usage-of");
+    let lib = Lib::from_path("sample", &crate_.path().join("src/lib.rs"));
+    let external = Reference::find_in_path(&crate_.path().join("tests"));
+    let users = users(&Usage::of(&lib, &external));
+    let used_by = |path: &str| {
+        users
+            .iter()
+            .find(|(used, _, _)| used == path)
+            .map(|(_, modules, external)| (modules.clone(), *external))
+            .unwrap()
+    };
+    // The glob import uses every item of `a`.
+    assert_eq!(used_by("sample::a::first"), (vec!["sample::b".into()], 0));
+    assert_eq!(used_by("sample::a::second"), (vec!["sample::b".into()], 0));
+    // `other` stands for `b`.
+    assert_eq!(used_by("sample::b::aliased"), (vec!["sample::c".into()], 0));
+    assert_eq!(used_by("sample::c::run"), (vec![], 0));
+    assert_eq!(used_by("sample::d::tested"), (vec![], 1));
+    assert_eq!(used_by("sample::d::unused"), (vec![], 0));
+}
+
+#[test]
+fn unused_with_and_without_tests() {
+    let crate_ = sample("usage-unused");
+    let output = crate_.thigpen(&["unused"]);
+    assert_eq!(
+        stdout(&output),
+        "\
+src/lib.rs:3: pub mod sample::c
+src/lib.rs:4: pub mod sample::d
+src/c.rs:3: pub fn sample::c::run
+src/d.rs:1: pub fn sample::d::tested
+src/d.rs:2: pub fn sample::d::unused
+5 unused pub items
+"
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = crate_.thigpen(&["unused", "--tests"]);
+    assert_eq!(
+        stdout(&output),
+        "\
+src/lib.rs:3: pub mod sample::c
+src/c.rs:3: pub fn sample::c::run
+src/d.rs:2: pub fn sample::d::unused
+3 unused pub items
+"
+    );
+}